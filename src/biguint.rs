@@ -0,0 +1,942 @@
+use crate::helpers::Hex;
+use rand::Rng;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+// arbitrary-precision unsigned integers, stored as little-endian u64 limbs. the top limb is never
+// zero, so zero is the empty vector and every number has exactly one representation
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BigUint(Vec<u64>);
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self(Vec::new())
+    }
+
+    pub fn one() -> Self {
+        Self(vec![1])
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self(limbs)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.0.first().map(|limb| limb & 1 == 1).unwrap_or(false)
+    }
+
+    pub fn is_even(&self) -> bool {
+        !self.is_odd()
+    }
+
+    // the number of bits needed to write the number down, which is 0 for zero
+    pub fn bits(&self) -> usize {
+        self.0
+            .last()
+            .map(|top| self.0.len() * 64 - top.leading_zeros() as usize)
+            .unwrap_or(0)
+    }
+
+    pub fn bit(&self, i: usize) -> bool {
+        self.0
+            .get(i / 64)
+            .map(|limb| (limb >> (i % 64)) & 1 == 1)
+            .unwrap_or(false)
+    }
+
+    pub fn set_bit(&mut self, i: usize) {
+        if self.0.len() <= i / 64 {
+            self.0.resize(i / 64 + 1, 0);
+        }
+        self.0[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.0.len() {
+            0 => Some(0),
+            1 => Some(self.0[0]),
+            _ => None,
+        }
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u64, |limb, byte| limb << 8 | *byte as u64)
+            })
+            .collect();
+
+        Self::from_limbs(limbs)
+    }
+
+    // the big-endian bytes of the number with no leading zeroes, so zero has no bytes at all
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes = self
+            .0
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes().to_vec())
+            .collect::<Vec<u8>>();
+        let zeroes = bytes.iter().take_while(|byte| **byte == 0).count();
+
+        bytes[zeroes..].to_vec()
+    }
+
+    // the big-endian bytes of the number, left-padded with zeroes to `length` bytes, or None if it
+    // doesn't fit
+    pub fn to_bytes_be_padded(&self, length: usize) -> Option<Vec<u8>> {
+        let bytes = self.to_bytes_be();
+        if bytes.len() > length {
+            return None;
+        }
+
+        Some([vec![0u8; length - bytes.len()], bytes].concat())
+    }
+
+    // a uniformly random number below 2^bits
+    pub fn random(bits: usize) -> Self {
+        Self::random_with(bits, &mut rand::thread_rng())
+    }
+
+    pub fn random_with<R>(bits: usize, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let mut limbs = (0..bits.div_ceil(64))
+            .map(|_| rng.gen::<u64>())
+            .collect::<Vec<u64>>();
+        if let Some(top) = limbs.last_mut() {
+            if !bits.is_multiple_of(64) {
+                *top >>= 64 - bits % 64;
+            }
+        }
+
+        Self::from_limbs(limbs)
+    }
+
+    // a uniformly random number in [0, bound)
+    pub fn random_below(bound: &Self) -> Self {
+        Self::random_below_with(bound, &mut rand::thread_rng())
+    }
+
+    pub fn random_below_with<R>(bound: &Self, rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(!bound.is_zero(), "the bound must be positive");
+
+        loop {
+            let candidate = Self::random_with(bound.bits(), rng);
+            if candidate < *bound {
+                return candidate;
+            }
+        }
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self.0.len() < other.0.len() {
+            return None;
+        }
+
+        let mut limbs = self.0.clone();
+        if sub_in_place(&mut limbs, &other.0) {
+            return None;
+        }
+
+        Some(Self::from_limbs(limbs))
+    }
+
+    pub fn divmod(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+
+        if divisor.0.len() == 1 {
+            let (quotient, remainder) = self.divmod_limb(divisor.0[0]);
+            return (quotient, Self::from(remainder));
+        }
+
+        divmod_knuth(&self.0, &divisor.0)
+    }
+
+    // the remainder mod a small number, without building the quotient
+    pub fn rem_u64(&self, divisor: u64) -> u64 {
+        assert!(divisor != 0, "attempt to divide by zero");
+
+        self.0.iter().rev().fold(0u64, |remainder, limb| {
+            (((remainder as u128) << 64 | *limb as u128) % divisor as u128) as u64
+        })
+    }
+
+    fn divmod_limb(&self, divisor: u64) -> (Self, u64) {
+        let mut quotient = vec![0u64; self.0.len()];
+        let mut remainder = 0u128;
+
+        for (i, limb) in self.0.iter().enumerate().rev() {
+            let dividend = remainder << 64 | *limb as u128;
+            quotient[i] = (dividend / divisor as u128) as u64;
+            remainder = dividend % divisor as u128;
+        }
+
+        (Self::from_limbs(quotient), remainder as u64)
+    }
+
+    pub fn pow(&self, exponent: u32) -> Self {
+        let mut result = Self::one();
+        for i in (0..32 - exponent.leading_zeros()).rev() {
+            result = &result * &result;
+            if (exponent >> i) & 1 == 1 {
+                result = &result * self;
+            }
+        }
+
+        result
+    }
+
+    // self^exponent mod modulus. odd moduli (which is all of them in practice) go through
+    // Montgomery multiplication with a sliding window over the exponent
+    pub fn modpow(&self, exponent: &Self, modulus: &Self) -> Self {
+        assert!(!modulus.is_zero(), "attempt to reduce modulo zero");
+
+        if *modulus == Self::one() {
+            return Self::zero();
+        }
+
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).pow(self, exponent);
+        }
+
+        let base = self % modulus;
+        let mut result = Self::one();
+        for i in (0..exponent.bits()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.bit(i) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+
+        result
+    }
+
+    // the inverse of self mod modulus, if self and modulus are coprime. this is the extended
+    // Euclidean algorithm, keeping only the coefficient of self, reduced mod modulus so that it
+    // never has to go negative
+    pub fn modinv(&self, modulus: &Self) -> Option<Self> {
+        let (mut r0, mut r1) = (modulus.clone(), self % modulus);
+        let (mut t0, mut t1) = (Self::zero(), Self::one());
+
+        while !r1.is_zero() {
+            let (quotient, remainder) = r0.divmod(&r1);
+            let t = &(&(&t0 + modulus) - &(&(&quotient * &t1) % modulus)) % modulus;
+
+            r0 = std::mem::replace(&mut r1, remainder);
+            t0 = std::mem::replace(&mut t1, t);
+        }
+
+        if r0 == Self::one() {
+            Some(&t0 % modulus)
+        } else {
+            None
+        }
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let remainder = &a % &b;
+            a = std::mem::replace(&mut b, remainder);
+        }
+
+        a
+    }
+
+    // the largest x with x^n <= self, by Newton's method starting from above the root
+    pub fn nth_root(&self, n: u32) -> Self {
+        assert!(n > 0, "there is no zeroth root");
+
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+
+        let n_big = Self::from(n as u64);
+        let n_minus_one = Self::from(n as u64 - 1);
+        let mut x = Self::one() << (self.bits() / n as usize + 1);
+
+        loop {
+            let y = &(&(&n_minus_one * &x) + &(self / &x.pow(n - 1))) / &n_big;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn cbrt(&self) -> Self {
+        self.nth_root(3)
+    }
+}
+
+// the x below the product of the moduli with x = r mod m for every (r, m), if the moduli are
+// pairwise coprime. each residue is scaled by the product of the other moduli, times that
+// product's inverse mod its own modulus, so that it vanishes mod every modulus but its own. a
+// modulus of 0 gives None
+pub fn chinese_remainder(congruences: &[(BigUint, BigUint)]) -> Option<BigUint> {
+    if congruences.iter().any(|(_, modulus)| modulus.is_zero()) {
+        return None;
+    }
+
+    let product = congruences
+        .iter()
+        .fold(BigUint::one(), |product, (_, modulus)| &product * modulus);
+
+    let mut x = BigUint::zero();
+    for (residue, modulus) in congruences {
+        let others = &product / modulus;
+        let inverse = others.modinv(modulus)?;
+        x = &x + &(&(&(residue * &others) % &product) * &inverse);
+    }
+
+    Some(&x % &product)
+}
+
+// adds `other` into `limbs`, growing it if there is a carry out of the top
+fn add_in_place(limbs: &mut Vec<u64>, other: &[u64]) {
+    if limbs.len() < other.len() {
+        limbs.resize(other.len(), 0);
+    }
+
+    let mut carry = false;
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let (sum, carry1) = limb.overflowing_add(other.get(i).copied().unwrap_or(0));
+        let (sum, carry2) = sum.overflowing_add(carry as u64);
+        *limb = sum;
+        carry = carry1 || carry2;
+
+        if !carry && i >= other.len() {
+            break;
+        }
+    }
+
+    if carry {
+        limbs.push(1);
+    }
+}
+
+// subtracts `other` from `limbs`, which must be at least as long, and returns whether it borrowed
+// out of the top (that is, whether `other` was bigger)
+fn sub_in_place(limbs: &mut [u64], other: &[u64]) -> bool {
+    let mut borrow = false;
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        let (difference, borrow1) = limb.overflowing_sub(other.get(i).copied().unwrap_or(0));
+        let (difference, borrow2) = difference.overflowing_sub(borrow as u64);
+        *limb = difference;
+        borrow = borrow1 || borrow2;
+
+        if !borrow && i >= other.len() {
+            break;
+        }
+    }
+
+    borrow
+}
+
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut product = vec![0u64; a.len() + b.len()];
+
+    for (i, x) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, y) in b.iter().enumerate() {
+            let sum = product[i + j] as u128 + *x as u128 * *y as u128 + carry;
+            product[i + j] = sum as u64;
+            carry = sum >> 64;
+        }
+        product[i + b.len()] = carry as u64;
+    }
+
+    product
+}
+
+// long division (Knuth's algorithm D) for a divisor of at least two limbs. both numbers are
+// shifted left until the top bit of the divisor is set, which makes each estimate of a quotient
+// limb from the top two limbs at most two too big
+fn divmod_knuth(dividend: &[u64], divisor: &[u64]) -> (BigUint, BigUint) {
+    let shift = divisor[divisor.len() - 1].leading_zeros() as usize;
+    let v = (BigUint(divisor.to_vec()) << shift).0;
+    let mut u = (BigUint(dividend.to_vec()) << shift).0;
+    u.resize(dividend.len() + 1, 0);
+
+    let n = v.len();
+    let m = dividend.len() - n;
+    let mut quotient = vec![0u64; m + 1];
+
+    for j in (0..=m).rev() {
+        let top = (u[j + n] as u128) << 64 | u[j + n - 1] as u128;
+        let mut q = top / v[n - 1] as u128;
+        let mut r = top % v[n - 1] as u128;
+
+        while q >> 64 != 0 || q * v[n - 2] as u128 > (r << 64 | u[j + n - 2] as u128) {
+            q -= 1;
+            r += v[n - 1] as u128;
+            if r >> 64 != 0 {
+                break;
+            }
+        }
+
+        // u[j..=j + n] -= q * v
+        let mut carry = 0u128;
+        let mut borrow = false;
+        for i in 0..n {
+            let product = q * v[i] as u128 + carry;
+            carry = product >> 64;
+
+            let (difference, borrow1) = u[i + j].overflowing_sub(product as u64);
+            let (difference, borrow2) = difference.overflowing_sub(borrow as u64);
+            u[i + j] = difference;
+            borrow = borrow1 || borrow2;
+        }
+        let (difference, borrow1) = u[j + n].overflowing_sub(carry as u64);
+        let (difference, borrow2) = difference.overflowing_sub(borrow as u64);
+        u[j + n] = difference;
+
+        // the estimate was still one too big, so add v back
+        if borrow1 || borrow2 {
+            q -= 1;
+
+            let mut carry = false;
+            for i in 0..n {
+                let (sum, carry1) = u[i + j].overflowing_add(v[i]);
+                let (sum, carry2) = sum.overflowing_add(carry as u64);
+                u[i + j] = sum;
+                carry = carry1 || carry2;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u64);
+        }
+
+        quotient[j] = q as u64;
+    }
+
+    u.truncate(n);
+    (
+        BigUint::from_limbs(quotient),
+        BigUint::from_limbs(u) >> shift,
+    )
+}
+
+// arithmetic mod an odd modulus m in Montgomery form, where x is represented by xR mod m with
+// R = 2^(64 * limbs). multiplying two representations and dividing by R can be done with
+// multiplications and shifts alone, with no division by m
+struct Montgomery {
+    modulus: Vec<u64>,
+    // -m^-1 mod 2^64
+    m_prime: u64,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigUint) -> Self {
+        // Newton's iteration doubles the number of correct low bits of the inverse each time
+        let m0 = modulus.0[0];
+        let mut inverse = 1u64;
+        for _ in 0..6 {
+            inverse = inverse.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inverse)));
+        }
+
+        Self {
+            modulus: modulus.0.clone(),
+            m_prime: inverse.wrapping_neg(),
+        }
+    }
+
+    // x -> xR mod m, and back again
+    fn encode(&self, x: &BigUint) -> Vec<u64> {
+        let n = self.modulus.len();
+        let mut limbs = (&(x << (64 * n)) % &BigUint(self.modulus.clone())).0;
+        limbs.resize(n, 0);
+        limbs
+    }
+
+    fn decode(&self, x: &[u64]) -> BigUint {
+        let mut one = vec![0u64; self.modulus.len()];
+        one[0] = 1;
+        BigUint::from_limbs(self.mul(x, &one))
+    }
+
+    // abR^-1 mod m, interleaving the multiplication with the reduction one limb at a time
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let m = &self.modulus;
+        let n = m.len();
+        let mut t = vec![0u64; n + 2];
+
+        for b_i in b {
+            let mut carry = 0u128;
+            for j in 0..n {
+                let sum = t[j] as u128 + a[j] as u128 * *b_i as u128 + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // adding a multiple of m clears the bottom limb, which is then shifted out
+            let k = t[0].wrapping_mul(self.m_prime) as u128;
+            let mut carry = (t[0] as u128 + k * m[0] as u128) >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128 + k * m[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+            t[n + 1] = 0;
+        }
+
+        // the result is below 2m, so at most one subtraction brings it into range
+        if t[n] != 0 || compare_limbs(&t[..n], m) != Ordering::Less {
+            sub_in_place(&mut t[..=n], m);
+        }
+
+        t.truncate(n);
+        t
+    }
+
+    fn pow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        let bits = exponent.bits();
+        let window = match bits {
+            0..=32 => 1,
+            33..=256 => 4,
+            _ => 5,
+        };
+
+        // the odd powers base^1, base^3, ..., base^(2^window - 1)
+        let base = self.encode(base);
+        let square = self.mul(&base, &base);
+        let mut odd_powers = vec![base];
+        for i in 1..1 << (window - 1) {
+            let next = self.mul(&odd_powers[i - 1], &square);
+            odd_powers.push(next);
+        }
+
+        let mut result = self.encode(&BigUint::one());
+        let mut i = bits;
+        while i > 0 {
+            if !exponent.bit(i - 1) {
+                result = self.mul(&result, &result);
+                i -= 1;
+                continue;
+            }
+
+            // the longest window of at most `window` bits ending in bit i - 1 that ends in a one
+            let mut low = i.saturating_sub(window);
+            while !exponent.bit(low) {
+                low += 1;
+            }
+
+            let mut value = 0;
+            for bit in (low..i).rev() {
+                result = self.mul(&result, &result);
+                value = value << 1 | exponent.bit(bit) as usize;
+            }
+            result = self.mul(&result, &odd_powers[value / 2]);
+            i = low;
+        }
+
+        self.decode(&result)
+    }
+}
+
+fn compare_limbs(a: &[u64], b: &[u64]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_limbs(&self.0, &other.0)
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        Self::from_limbs(vec![n])
+    }
+}
+
+impl From<&Hex> for BigUint {
+    fn from(hex: &Hex) -> Self {
+        Self::from_bytes_be(&hex.to_bytes())
+    }
+}
+
+impl From<&BigUint> for Hex {
+    fn from(n: &BigUint) -> Self {
+        Hex::from_bytes(&n.to_bytes_be())
+    }
+}
+
+// decimal, nineteen digits at a time
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+
+        let mut chunks = Vec::new();
+        let mut n = self.clone();
+        while !n.is_zero() {
+            let (quotient, remainder) = n.divmod_limb(CHUNK);
+            chunks.push(remainder);
+            n = quotient;
+        }
+
+        match chunks.split_last() {
+            None => write!(f, "0"),
+            Some((top, rest)) => {
+                write!(f, "{}", top)?;
+                for chunk in rest.iter().rev() {
+                    write!(f, "{:019}", chunk)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut limbs = self.0.clone();
+        add_in_place(&mut limbs, &other.0);
+        BigUint(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        self.checked_sub(other)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.0, &other.0))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.divmod(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.divmod(other).1
+    }
+}
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+
+        let (limbs, bits) = (bits / 64, bits % 64);
+        let mut shifted = vec![0u64; limbs];
+        let mut carry = 0;
+        for limb in &self.0 {
+            shifted.push(limb << bits | carry);
+            carry = if bits == 0 { 0 } else { limb >> (64 - bits) };
+        }
+        shifted.push(carry);
+
+        BigUint::from_limbs(shifted)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        let (limbs, bits) = (bits / 64, bits % 64);
+        let high = self.0.iter().skip(limbs + 1).chain(std::iter::once(&0));
+        let shifted = self
+            .0
+            .iter()
+            .skip(limbs)
+            .zip(high)
+            .map(|(low, high)| {
+                if bits == 0 {
+                    *low
+                } else {
+                    low >> bits | high << (64 - bits)
+                }
+            })
+            .collect();
+
+        BigUint::from_limbs(shifted)
+    }
+}
+
+// the owned versions of the operators just borrow
+macro_rules! forward_binary_op {
+    ($trait:ident, $method:ident) => {
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $trait<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl $trait<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binary_op!(Add, add);
+forward_binary_op!(Sub, sub);
+forward_binary_op!(Mul, mul);
+forward_binary_op!(Div, div);
+forward_binary_op!(Rem, rem);
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+#[cfg(test)]
+fn to_bignum(n: &BigUint) -> openssl::bn::BigNum {
+    openssl::bn::BigNum::from_slice(&n.to_bytes_be()).unwrap()
+}
+
+// random numbers of all sorts of sizes, including the edge cases where limbs are all ones or all
+// zeroes that tend to break carries
+#[cfg(test)]
+fn random_operand() -> BigUint {
+    let bits = rand::random::<usize>() % 2200;
+    match rand::random::<u8>() % 4 {
+        0 => &(BigUint::one() << bits) - &BigUint::one(),
+        1 => BigUint::one() << bits,
+        _ => BigUint::random(bits),
+    }
+}
+
+#[test]
+fn test_conversions() {
+    let hex = "000123456789abcdeffedcba98765432100f";
+    let n = BigUint::from(&Hex::try_from(hex).unwrap());
+    assert_eq!(Hex::from(&n).to_string(), hex[2..]);
+    assert_eq!(n.to_bytes_be_padded(17).unwrap()[0], 0x01);
+    assert_eq!(n.to_bytes_be_padded(20).unwrap().len(), 20);
+    assert!(n.to_bytes_be_padded(16).is_none());
+
+    assert_eq!(BigUint::zero().to_bytes_be(), Vec::<u8>::new());
+    assert_eq!(BigUint::zero().to_string(), "0");
+    assert_eq!(BigUint::from(12345).to_u64(), Some(12345));
+
+    for _ in 0..100 {
+        let n = random_operand();
+        assert_eq!(BigUint::from_bytes_be(&n.to_bytes_be()), n);
+        assert_eq!(
+            n.to_string(),
+            to_bignum(&n).to_dec_str().unwrap().to_string()
+        );
+        assert_eq!(n.bits(), to_bignum(&n).num_bits() as usize);
+    }
+}
+
+#[test]
+fn test_arithmetic() {
+    for _ in 0..500 {
+        let (a, b) = (random_operand(), random_operand());
+        let (big_a, big_b) = (to_bignum(&a), to_bignum(&b));
+
+        assert_eq!((&a + &b).to_bytes_be(), (&big_a + &big_b).to_vec());
+        assert_eq!((&a * &b).to_bytes_be(), (&big_a * &big_b).to_vec());
+        assert_eq!(a.cmp(&b), big_a.cmp(&big_b));
+
+        if a >= b {
+            assert_eq!((&a - &b).to_bytes_be(), (&big_a - &big_b).to_vec());
+        } else {
+            assert!(a.checked_sub(&b).is_none());
+        }
+
+        if !b.is_zero() {
+            let (quotient, remainder) = a.divmod(&b);
+            assert_eq!(quotient.to_bytes_be(), (&big_a / &big_b).to_vec());
+            assert_eq!(remainder.to_bytes_be(), (&big_a % &big_b).to_vec());
+        }
+
+        let shift = rand::random::<usize>() % 200;
+        assert_eq!(&(&a << shift) >> shift, a);
+        assert_eq!(
+            (&a >> shift).to_bytes_be(),
+            (&big_a >> shift as i32).to_vec()
+        );
+    }
+}
+
+#[test]
+fn test_modular_arithmetic() {
+    let mut context = openssl::bn::BigNumContext::new().unwrap();
+
+    for i in 0..50 {
+        let bits = [64, 512, 1024, 2048][i % 4];
+        let mut modulus = BigUint::random(bits);
+        if i % 5 != 0 {
+            // mostly odd moduli, for Montgomery, with the odd even one
+            modulus = &(&modulus >> 1 << 1) + &BigUint::one();
+        }
+        if modulus.is_zero() {
+            continue;
+        }
+        let (base, exponent) = (BigUint::random(bits + 10), BigUint::random(bits));
+
+        let mut expected = openssl::bn::BigNum::new().unwrap();
+        expected
+            .mod_exp(
+                &to_bignum(&base),
+                &to_bignum(&exponent),
+                &to_bignum(&modulus),
+                &mut context,
+            )
+            .unwrap();
+        assert_eq!(
+            base.modpow(&exponent, &modulus).to_bytes_be(),
+            expected.to_vec()
+        );
+
+        let mut gcd = openssl::bn::BigNum::new().unwrap();
+        gcd.gcd(&to_bignum(&base), &to_bignum(&modulus), &mut context)
+            .unwrap();
+        assert_eq!(base.gcd(&modulus).to_bytes_be(), gcd.to_vec());
+
+        match base.modinv(&modulus) {
+            Some(inverse) => {
+                assert_eq!(base.gcd(&modulus), BigUint::one());
+                assert_eq!(&(&base * &inverse) % &modulus, &BigUint::one() % &modulus);
+            }
+            None => assert_ne!(base.gcd(&modulus), BigUint::one()),
+        }
+    }
+
+    assert_eq!(
+        BigUint::from(3).modinv(&BigUint::from(7)),
+        Some(BigUint::from(5))
+    );
+    assert_eq!(BigUint::from(4).modinv(&BigUint::from(8)), None);
+    assert_eq!(
+        BigUint::from(5).modpow(&BigUint::zero(), &BigUint::from(7)),
+        BigUint::one()
+    );
+}
+
+#[test]
+fn test_nth_root() {
+    for _ in 0..100 {
+        let n = random_operand();
+        let k = 1 + rand::random::<u32>() % 7;
+        let root = n.nth_root(k);
+
+        assert!(root.pow(k) <= n);
+        assert!((&root + &BigUint::one()).pow(k) > n);
+    }
+
+    let x = BigUint::random(700);
+    assert_eq!(x.pow(3).cbrt(), x);
+}
+
+#[test]
+fn test_chinese_remainder() {
+    let congruences = |pairs: &[(u64, u64)]| {
+        pairs
+            .iter()
+            .map(|(r, m)| (BigUint::from(*r), BigUint::from(*m)))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        chinese_remainder(&congruences(&[(2, 3), (3, 5), (2, 7)])),
+        Some(BigUint::from(23))
+    );
+    assert_eq!(chinese_remainder(&congruences(&[(1, 4), (3, 6)])), None);
+    assert_eq!(chinese_remainder(&congruences(&[(2, 3), (0, 0)])), None);
+
+    // big random moduli, which are coprime often enough
+    for _ in 0..20 {
+        let moduli = (0..4).map(|_| random_operand()).collect::<Vec<_>>();
+        let x = BigUint::random(200);
+        let pairs = moduli
+            .iter()
+            .filter(|modulus| !modulus.is_zero())
+            .map(|modulus| (&x % modulus, modulus.clone()))
+            .collect::<Vec<_>>();
+
+        match chinese_remainder(&pairs) {
+            Some(solution) => pairs
+                .iter()
+                .for_each(|(residue, modulus)| assert_eq!(&solution % modulus, *residue)),
+            None => assert!(pairs.iter().enumerate().any(|(i, (_, a))| pairs[i + 1..]
+                .iter()
+                .any(|(_, b)| a.gcd(b) != BigUint::one()))),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::helpers;
 use openssl::symm::{self, decrypt, encrypt, Cipher};
 
+#[derive(Clone, Copy)]
 pub enum Mode {
     Cbc,
     Ctr,
@@ -250,7 +251,7 @@ pub struct EcbCrypter {
 }
 
 impl EcbCrypter {
-    fn new(key: [u8; Aes::BLOCK_SIZE]) -> Self {
+    pub fn new(key: [u8; Aes::BLOCK_SIZE]) -> Self {
         let openssl_cipher = Cipher::aes_128_ecb();
 
         Self {
@@ -259,7 +260,7 @@ impl EcbCrypter {
         }
     }
 
-    fn encrypt<T>(&self, plaintext: T) -> Vec<u8>
+    pub fn encrypt<T>(&self, plaintext: T) -> Vec<u8>
     where
         T: AsRef<[u8]>,
     {
@@ -0,0 +1,188 @@
+use crate::biguint::BigUint;
+use crate::helpers::Base64;
+use std::convert::TryFrom;
+
+// just enough DER (the distinguished encoding rules for ASN.1) and PEM to read and write key
+// files. every value is a tag, a length and the contents
+
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const SEQUENCE: u8 = 0x30;
+
+// lengths below 128 take one byte. longer ones take a byte with the top bit set that says how
+// many big-endian bytes of length follow
+pub fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let length = contents.len();
+    let length_bytes = if length < 0x80 {
+        vec![length as u8]
+    } else {
+        let bytes = length.to_be_bytes();
+        let significant = &bytes[bytes.iter().take_while(|byte| **byte == 0).count()..];
+        [&[0x80 | significant.len() as u8][..], significant].concat()
+    };
+
+    [&[tag][..], &length_bytes, contents].concat()
+}
+
+// integers are two's complement, so a positive one whose top bit is set needs a zero byte in
+// front of it
+pub fn integer(n: &BigUint) -> Vec<u8> {
+    let bytes = n.to_bytes_be();
+    match bytes.first() {
+        None => encode(INTEGER, &[0]),
+        Some(byte) if byte & 0x80 != 0 => encode(INTEGER, &[&[0][..], &bytes].concat()),
+        Some(_) => encode(INTEGER, &bytes),
+    }
+}
+
+pub fn sequence(items: &[Vec<u8>]) -> Vec<u8> {
+    encode(SEQUENCE, &items.concat())
+}
+
+// reads values off the front of some DER
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    // the contents of the next value, which must have the given tag
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8], &'static str> {
+        let (found, rest) = self.data.split_first().ok_or("unexpected end of DER")?;
+        if *found != tag {
+            return Err("unexpected DER tag");
+        }
+
+        let (first, rest) = rest.split_first().ok_or("unexpected end of DER")?;
+        let (length, rest) = if first & 0x80 == 0 {
+            (*first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 8 || rest.len() < count {
+                return Err("invalid DER length");
+            }
+            let length = rest[..count]
+                .iter()
+                .fold(0usize, |length, byte| length << 8 | *byte as usize);
+            (length, &rest[count..])
+        };
+
+        if rest.len() < length {
+            return Err("DER value runs past the end of the data");
+        }
+
+        let (contents, rest) = rest.split_at(length);
+        self.data = rest;
+        Ok(contents)
+    }
+
+    pub fn integer(&mut self) -> Result<BigUint, &'static str> {
+        let contents = self.read(INTEGER)?;
+        match contents.first() {
+            None => Err("empty DER integer"),
+            Some(byte) if byte & 0x80 != 0 => Err("negative DER integer"),
+            Some(_) => Ok(BigUint::from_bytes_be(contents)),
+        }
+    }
+
+    pub fn sequence(&mut self) -> Result<Reader<'a>, &'static str> {
+        self.read(SEQUENCE).map(Reader::new)
+    }
+
+    // fails if anything is left over
+    pub fn finish(self) -> Result<(), &'static str> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err("trailing data after DER value")
+        }
+    }
+}
+
+// DER in base64 between "-----BEGIN <label>-----" and "-----END <label>-----" lines, wrapped at 64
+// characters
+pub fn pem_encode(label: &str, der: &[u8]) -> String {
+    let base64 = Base64::from(der).to_string();
+    let lines = base64
+        .as_bytes()
+        .chunks(64)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect::<Vec<String>>();
+
+    format!(
+        "-----BEGIN {}-----\n{}\n-----END {}-----\n",
+        label,
+        lines.join("\n"),
+        label
+    )
+}
+
+// the label and DER from the first PEM block in `pem`
+pub fn pem_decode(pem: &str) -> Result<(String, Vec<u8>), &'static str> {
+    let mut lines = pem
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    let label = lines
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+        .ok_or("missing PEM header")?
+        .to_string();
+    let footer = format!("-----END {}-----", label);
+
+    let mut base64 = String::new();
+    for line in lines {
+        if line == footer {
+            let der = Base64::try_from(&base64[..])?.to_bytes();
+            return Ok((label, der));
+        }
+        base64.push_str(line);
+    }
+
+    Err("missing PEM footer")
+}
+
+#[test]
+fn test_der() {
+    // lengths of every size, and integers that do and don't need a sign byte
+    for length in [0, 1, 127, 128, 255, 256, 70000].iter() {
+        let contents = vec![0x42; *length];
+        let encoded = encode(OCTET_STRING, &contents);
+        let mut reader = Reader::new(&encoded);
+        assert_eq!(reader.read(OCTET_STRING).unwrap(), &contents[..]);
+        assert!(reader.is_empty());
+    }
+
+    assert_eq!(integer(&BigUint::zero()), vec![0x02, 0x01, 0x00]);
+    assert_eq!(integer(&BigUint::from(0x7f)), vec![0x02, 0x01, 0x7f]);
+    assert_eq!(integer(&BigUint::from(0x80)), vec![0x02, 0x02, 0x00, 0x80]);
+
+    let n = BigUint::random(2048);
+    let der = sequence(&[integer(&n), integer(&BigUint::from(65537))]);
+    let mut reader = Reader::new(&der);
+    let mut inner = reader.sequence().unwrap();
+    assert_eq!(inner.integer().unwrap(), n);
+    assert_eq!(inner.integer().unwrap(), BigUint::from(65537));
+    assert!(inner.finish().is_ok());
+    assert!(reader.finish().is_ok());
+
+    // a wrong tag, and a length that runs off the end
+    assert!(Reader::new(&der).read(INTEGER).is_err());
+    assert!(Reader::new(&der[..der.len() - 1]).sequence().is_err());
+
+    let pem = pem_encode("TEST", &der);
+    assert!(pem.lines().all(|line| line.len() <= 64));
+    assert_eq!(pem_decode(&pem).unwrap(), ("TEST".to_string(), der));
+}
@@ -0,0 +1,218 @@
+use crate::biguint::BigUint;
+use crate::block_ciphers::{Aes, Input, Mode};
+use crate::helpers::Hex;
+use crate::sha1::Sha1;
+use rand::Rng;
+use std::convert::TryFrom;
+
+// the MODP groups from RFC 3526, all with generator 2
+const MODP_1536: &str = concat!(
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+    "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+    "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+    "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+    "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+    "9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff",
+);
+
+const MODP_2048: &str = concat!(
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+    "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+    "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+    "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+    "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+    "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+    "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+    "3995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff",
+);
+
+const MODP_3072: &str = concat!(
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+    "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+    "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+    "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+    "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+    "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+    "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+    "3995497cea956ae515d2261898fa051015728e5a8aaac42dad33170d04507a33",
+    "a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7db3970f85a6e1e4c7",
+    "abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864",
+    "d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e2",
+    "08e24fa074e5ab3143db5bfce0fd108e4b82d120a93ad2caffffffffffffffff",
+);
+
+const MODP_4096: &str = concat!(
+    "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74",
+    "020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f1437",
+    "4fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7ed",
+    "ee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf05",
+    "98da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb",
+    "9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3b",
+    "e39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf695581718",
+    "3995497cea956ae515d2261898fa051015728e5a8aaac42dad33170d04507a33",
+    "a85521abdf1cba64ecfb850458dbef0a8aea71575d060c7db3970f85a6e1e4c7",
+    "abf5ae8cdb0933d71e8c94e04a25619dcee3d2261ad2ee6bf12ffa06d98a0864",
+    "d87602733ec86a64521f2b18177b200cbbe117577a615d6c770988c0bad946e2",
+    "08e24fa074e5ab3143db5bfce0fd108e4b82d120a92108011a723c12a787e6d7",
+    "88719a10bdba5b2699c327186af4e23c1a946834b6150bda2583e9ca2ad44ce8",
+    "dbbbc2db04de8ef92e8efc141fbecaa6287c59474e6bc05d99b2964fa090c3a2",
+    "233ba186515be7ed1f612970cee2d7afb81bdd762170481cd0069127d5b05aa9",
+    "93b4ea988d8fddc186ffb7dc90a6c08f4df435c934063199ffffffffffffffff",
+);
+
+// a Diffie-Hellman group: everybody agrees on a prime p and a generator g of (a large subgroup
+// of) the integers mod p
+#[derive(Clone, Debug, PartialEq)]
+pub struct Group {
+    pub p: BigUint,
+    pub g: BigUint,
+}
+
+impl Group {
+    pub fn new(p: BigUint, g: BigUint) -> Self {
+        Self { p, g }
+    }
+
+    fn modp(p: &str) -> Self {
+        Self::new(BigUint::from(&Hex::try_from(p).unwrap()), BigUint::from(2))
+    }
+
+    pub fn modp_1536() -> Self {
+        Self::modp(MODP_1536)
+    }
+
+    pub fn modp_2048() -> Self {
+        Self::modp(MODP_2048)
+    }
+
+    pub fn modp_3072() -> Self {
+        Self::modp(MODP_3072)
+    }
+
+    pub fn modp_4096() -> Self {
+        Self::modp(MODP_4096)
+    }
+
+    // a group small enough to work through by hand
+    pub fn toy() -> Self {
+        Self::new(BigUint::from(37), BigUint::from(5))
+    }
+
+    // a private key in [1, p - 1) and the public key g^private mod p that goes with it
+    pub fn generate_keypair<R>(&self, rng: &mut R) -> Keypair
+    where
+        R: Rng + ?Sized,
+    {
+        let private =
+            &BigUint::random_below_with(&(&self.p - &BigUint::from(2)), rng) + &BigUint::one();
+        let public = self.g.modpow(&private, &self.p);
+
+        Keypair { private, public }
+    }
+}
+
+pub struct Keypair {
+    private: BigUint,
+    pub public: BigUint,
+}
+
+impl Keypair {
+    // (g^b)^a = (g^a)^b mod p, which both sides can compute but nobody listening in can
+    pub fn shared_secret(&self, group: &Group, other_public: &BigUint) -> BigUint {
+        other_public.modpow(&self.private, &group.p)
+    }
+}
+
+// an AES-128 key from the first 16 bytes of the SHA-1 hash of the shared secret
+pub fn derive_key(secret: &BigUint) -> [u8; Aes::BLOCK_SIZE] {
+    let mut key = [0u8; Aes::BLOCK_SIZE];
+    key.copy_from_slice(&Sha1::digest(secret.to_bytes_be())[..Aes::BLOCK_SIZE]);
+    key
+}
+
+// both ends of a conversation after a key exchange. every message is encrypted with AES-CBC under
+// the derived key and a fresh random IV, which is sent along after the ciphertext
+pub struct Channel {
+    aes: Aes,
+}
+
+impl Channel {
+    pub fn new(secret: &BigUint) -> Self {
+        Self {
+            aes: Aes::new(derive_key(secret), Mode::Cbc),
+        }
+    }
+
+    pub fn seal<T>(&mut self, plaintext: T) -> Vec<u8>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut iv = [0u8; Aes::BLOCK_SIZE];
+        iv.iter_mut().for_each(|byte| *byte = rand::random());
+
+        let ciphertext = self.aes.encrypt(plaintext, Input::Iv(iv)).unwrap();
+        [&ciphertext[..], &iv].concat()
+    }
+
+    pub fn open<T>(&mut self, message: T) -> Result<Vec<u8>, &'static str>
+    where
+        T: AsRef<[u8]>,
+    {
+        let message = message.as_ref();
+        if message.len() < 2 * Aes::BLOCK_SIZE {
+            return Err("the message is too short");
+        }
+
+        let (ciphertext, iv_bytes) = message.split_at(message.len() - Aes::BLOCK_SIZE);
+        let mut iv = [0u8; Aes::BLOCK_SIZE];
+        iv.copy_from_slice(iv_bytes);
+
+        self.aes.decrypt(ciphertext, Input::Iv(iv))
+    }
+}
+
+#[test]
+fn test_groups() {
+    use openssl::bn::BigNum;
+
+    let groups = [
+        (Group::modp_1536(), BigNum::get_rfc3526_prime_1536()),
+        (Group::modp_2048(), BigNum::get_rfc3526_prime_2048()),
+        (Group::modp_3072(), BigNum::get_rfc3526_prime_3072()),
+        (Group::modp_4096(), BigNum::get_rfc3526_prime_4096()),
+    ];
+
+    for (group, expected) in groups.iter() {
+        assert_eq!(group.p.to_bytes_be(), expected.as_ref().unwrap().to_vec());
+        assert_eq!(group.g, BigUint::from(2));
+    }
+}
+
+#[test]
+fn test_pluggable_rng() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let group = Group::modp_1536();
+    let keypair1 = group.generate_keypair(&mut StdRng::seed_from_u64(33));
+    let keypair2 = group.generate_keypair(&mut StdRng::seed_from_u64(33));
+    let keypair3 = group.generate_keypair(&mut StdRng::seed_from_u64(34));
+
+    assert_eq!(keypair1.public, keypair2.public);
+    assert_ne!(keypair1.public, keypair3.public);
+}
+
+#[test]
+fn test_channel() {
+    let secret = BigUint::random(1536);
+    let (mut alice, mut bob) = (Channel::new(&secret), Channel::new(&secret));
+
+    let message = alice.seal("hello bob");
+    assert_eq!(bob.open(&message).unwrap(), b"hello bob");
+
+    // the same plaintext never encrypts the same way twice
+    assert_ne!(alice.seal("hello bob"), message);
+
+    // and a different secret can't read it
+    let mut eve = Channel::new(&(&secret + &BigUint::one()));
+    assert_ne!(eve.open(&message), Ok(b"hello bob".to_vec()));
+}
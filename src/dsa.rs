@@ -0,0 +1,198 @@
+use crate::biguint::BigUint;
+use crate::helpers::Hex;
+use crate::sha1::Sha1;
+use std::convert::TryFrom;
+
+// DSA (FIPS 186), over SHA-1. the group is the order q subgroup of the integers mod p, generated
+// by g, and a signature is two numbers mod q
+
+// the 1024-bit p and 160-bit q that cryptopals uses
+const P: &str = concat!(
+    "800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65e",
+    "ac698c1702578b07dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc5",
+    "65f3bf6b50929139ebeac04f48c3c84afb796d61e5a4f9a8fda812ab59494232",
+    "c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc871a584471bb1",
+);
+
+const Q: &str = "f4f47f05794b256174bba6e9b396a7707e563c5b";
+
+const G: &str = concat!(
+    "5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa40",
+    "46c8db53039db620c094c9fa077ef389b5322a559946a71903f990f1f7e0e025",
+    "e2d7f7cf494aff1a0470f5b64c36b625a097f1651fe775323556fe00b3608c88",
+    "7892878480e99041be601a62166ca6894bdd41a7054ec89f756ba9fc95302291",
+);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameters {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl Parameters {
+    pub fn new(p: BigUint, q: BigUint, g: BigUint) -> Self {
+        Self { p, q, g }
+    }
+
+    pub fn cryptopals() -> Self {
+        let parse = |hex: &str| BigUint::from(&Hex::try_from(hex).unwrap());
+        Self::new(parse(P), parse(Q), parse(G))
+    }
+
+    // whether g really does generate a subgroup of order q: 1 < g < p and g^q = 1 mod p. this
+    // doesn't check that p and q are prime
+    pub fn is_valid(&self) -> bool {
+        let one = BigUint::one();
+        self.g > one
+            && self.g < self.p
+            && (&(&self.p - &one) % &self.q).is_zero()
+            && self.g.modpow(&self.q, &self.p) == one
+    }
+}
+
+// H(m), as a number. a SHA-1 hash is as long as q, so it is used whole
+pub fn hash(message: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&Sha1::digest(message))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+    pub parameters: Parameters,
+    pub y: BigUint,
+}
+
+impl PublicKey {
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let q = &self.parameters.q;
+        let in_range = |n: &BigUint| !n.is_zero() && n < q;
+
+        in_range(&signature.r)
+            && in_range(&signature.s)
+            && self.verify_unchecked(message, signature)
+    }
+
+    // verification without checking that 0 < r < q and 0 < s < q first. with w = s^-1, the
+    // signature is good if (g^(H(m)w) * y^(rw) mod p) mod q = r
+    pub fn verify_unchecked(&self, message: &[u8], signature: &Signature) -> bool {
+        let Parameters { p, q, g } = &self.parameters;
+        let w = match signature.s.modinv(q) {
+            Some(w) => w,
+            None => return false,
+        };
+
+        let u1 = &(&hash(message) * &w) % q;
+        let u2 = &(&signature.r * &w) % q;
+        let v = &(&(&g.modpow(&u1, p) * &self.y.modpow(&u2, p)) % p) % q;
+
+        v == signature.r
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrivateKey {
+    pub parameters: Parameters,
+    pub x: BigUint,
+}
+
+impl PrivateKey {
+    // x is random in [1, q)
+    pub fn generate(parameters: &Parameters) -> Self {
+        let x = &BigUint::random_below(&(&parameters.q - &BigUint::one())) + &BigUint::one();
+
+        Self {
+            parameters: parameters.clone(),
+            x,
+        }
+    }
+
+    // y = g^x mod p
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            parameters: self.parameters.clone(),
+            y: self.parameters.g.modpow(&self.x, &self.parameters.p),
+        }
+    }
+
+    // signs with a fresh random nonce, trying again in the unlikely case that r or s is 0. bad
+    // parameters are refused up front, since with g = 0 (say) r is always 0 and no nonce would do
+    pub fn sign(&self, message: &[u8]) -> Result<Signature, &'static str> {
+        if !self.parameters.is_valid() {
+            return Err("invalid parameters");
+        }
+        let q = &self.parameters.q;
+
+        loop {
+            let k = &BigUint::random_below(&(q - &BigUint::one())) + &BigUint::one();
+            let signature = self.sign_with_nonce(message, &k);
+            if !signature.r.is_zero() && !signature.s.is_zero() {
+                return Ok(signature);
+            }
+        }
+    }
+
+    // r = (g^k mod p) mod q and s = k^-1 (H(m) + xr) mod q, for a nonce k in [1, q) that must be
+    // secret, random and never used twice
+    pub fn sign_with_nonce(&self, message: &[u8], k: &BigUint) -> Signature {
+        let Parameters { p, q, g } = &self.parameters;
+
+        let r = &g.modpow(k, p) % q;
+        let k_inverse = k.modinv(q).expect("the nonce must be invertible mod q");
+        let s = &(&k_inverse * &(&(&hash(message) + &(&self.x * &r)) % q)) % q;
+
+        Signature { r, s }
+    }
+}
+
+#[test]
+fn test_dsa() {
+    use openssl::bn::BigNum;
+    use openssl::dsa::{Dsa, DsaSig};
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::sign::Verifier;
+
+    let parameters = Parameters::cryptopals();
+    assert!(parameters.is_valid());
+    assert_eq!(parameters.q.bits(), 160);
+
+    let key = PrivateKey::generate(&parameters);
+    let public_key = key.public_key();
+    let message = b"a message worth signing";
+
+    let signature = key.sign(message).unwrap();
+    assert!(public_key.verify(message, &signature));
+    assert!(!public_key.verify(b"a different message", &signature));
+    assert!(!public_key.verify(
+        message,
+        &Signature {
+            r: &signature.r + &parameters.q,
+            s: signature.s.clone(),
+        }
+    ));
+
+    // openssl agrees about the signature
+    let to_bignum = |n: &BigUint| BigNum::from_slice(&n.to_bytes_be()).unwrap();
+    let openssl_key = Dsa::from_public_components(
+        to_bignum(&parameters.p),
+        to_bignum(&parameters.q),
+        to_bignum(&parameters.g),
+        to_bignum(&public_key.y),
+    )
+    .unwrap();
+    let pkey = PKey::from_dsa(openssl_key).unwrap();
+    let der = DsaSig::from_private_components(to_bignum(&signature.r), to_bignum(&signature.s))
+        .unwrap()
+        .to_der()
+        .unwrap();
+
+    let mut verifier = Verifier::new(MessageDigest::sha1(), &pkey).unwrap();
+    verifier.update(message).unwrap();
+    assert!(verifier.verify(&der).unwrap());
+}
@@ -0,0 +1,273 @@
+// Arithmetic over GF(2). Bit vectors are packed little-endian into u64 words, so bit i of a
+// vector lives in word i / 64 at position i % 64.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial(Vec<u64>);
+
+impl Polynomial {
+    pub fn one() -> Self {
+        Self(vec![1])
+    }
+
+    pub fn degree(&self) -> Option<usize> {
+        self.0
+            .iter()
+            .rposition(|word| *word != 0)
+            .map(|i| i * 64 + 63 - self.0[i].leading_zeros() as usize)
+    }
+
+    pub fn coefficient(&self, i: usize) -> bool {
+        self.0
+            .get(i / 64)
+            .map(|word| (word >> (i % 64)) & 1 == 1)
+            .unwrap_or(false)
+    }
+
+    // finds the minimal polynomial of a linearly recurrent bit sequence with the
+    // Berlekamp-Massey algorithm. the sequence needs to be at least twice as long as the degree of
+    // the polynomial
+    pub fn minimal_polynomial(sequence: &[bool]) -> Self {
+        let n = sequence.len();
+        let words = n / 64 + 2;
+
+        // the sequence is stored back to front so that the discrepancy at step i is the dot
+        // product of the connection polynomial with a contiguous window of the stored bits
+        let mut reversed = vec![0u64; words];
+        for (i, bit) in sequence.iter().enumerate() {
+            if *bit {
+                let j = n - 1 - i;
+                reversed[j / 64] |= 1 << (j % 64);
+            }
+        }
+
+        let mut connection = vec![0u64; words];
+        connection[0] = 1;
+        let mut previous = connection.clone();
+        let mut length = 0;
+        let mut shift = 1;
+
+        for i in 0..n {
+            let offset = n - 1 - i;
+            let discrepancy = (0..=length / 64).fold(0u32, |acc, k| {
+                acc ^ (connection[k] & window(&reversed, offset + k * 64)).count_ones()
+            }) & 1;
+
+            if discrepancy == 0 {
+                shift += 1;
+            } else if 2 * length <= i {
+                let temporary = connection.clone();
+                xor_shifted(&mut connection, &previous, shift);
+                length = i + 1 - length;
+                previous = temporary;
+                shift = 1;
+            } else {
+                xor_shifted(&mut connection, &previous, shift);
+                shift += 1;
+            }
+        }
+
+        // the characteristic polynomial is the reciprocal of the connection polynomial
+        let mut coefficients = vec![0u64; length / 64 + 1];
+        for i in 0..=length {
+            if (connection[i / 64] >> (i % 64)) & 1 == 1 {
+                let j = length - i;
+                coefficients[j / 64] |= 1 << (j % 64);
+            }
+        }
+
+        Self(coefficients)
+    }
+
+    // computes x^exponent mod self by square-and-multiply
+    pub fn x_pow_mod(&self, exponent: u64) -> Self {
+        let degree = self
+            .degree()
+            .expect("cannot reduce modulo the zero polynomial");
+        let reducer = Reducer::new(self, degree);
+        let mut result = Self::one();
+
+        for bit in (0..64 - exponent.leading_zeros()).rev() {
+            result = reducer.reduce(result.square());
+
+            if (exponent >> bit) & 1 == 1 {
+                result = reducer.reduce(result.mul_x());
+            }
+        }
+
+        result
+    }
+
+    fn square(&self) -> Self {
+        let spread = |half: u32| -> u64 {
+            (0..32).fold(0, |acc, i| acc | ((((half >> i) & 1) as u64) << (2 * i)))
+        };
+
+        Self(
+            self.0
+                .iter()
+                .flat_map(|word| vec![spread(*word as u32), spread((word >> 32) as u32)])
+                .collect(),
+        )
+    }
+
+    fn mul_x(&self) -> Self {
+        let mut words = vec![0u64; self.0.len() + 1];
+        for (i, word) in self.0.iter().enumerate() {
+            words[i] |= word << 1;
+            words[i + 1] |= word >> 63;
+        }
+
+        Self(words)
+    }
+}
+
+// precomputes the modulus shifted by every offset within a word, so that each step of the long
+// division is a word-aligned XOR
+struct Reducer {
+    degree: usize,
+    shifted: Vec<Vec<u64>>,
+}
+
+impl Reducer {
+    fn new(modulus: &Polynomial, degree: usize) -> Self {
+        let shifted = (0..64)
+            .map(|s| {
+                let mut words = vec![0u64; modulus.0.len() + 1];
+                xor_shifted(&mut words, &modulus.0, s);
+                words
+            })
+            .collect();
+
+        Self { degree, shifted }
+    }
+
+    fn reduce(&self, mut polynomial: Polynomial) -> Polynomial {
+        while let Some(degree) = polynomial.degree() {
+            if degree < self.degree {
+                break;
+            }
+
+            let offset = degree - self.degree;
+            let modulus = &self.shifted[offset % 64];
+            for (word, m) in polynomial.0[offset / 64..].iter_mut().zip(modulus) {
+                *word ^= m;
+            }
+        }
+
+        polynomial.0.truncate(self.degree / 64 + 1);
+        polynomial
+    }
+}
+
+// the 64 bits of a packed bit vector starting at an arbitrary bit offset
+fn window(bits: &[u64], offset: usize) -> u64 {
+    let (i, s) = (offset / 64, offset % 64);
+    let low = bits.get(i).copied().unwrap_or(0) >> s;
+    let high = if s == 0 {
+        0
+    } else {
+        bits.get(i + 1).copied().unwrap_or(0) << (64 - s)
+    };
+
+    low | high
+}
+
+// XORs `source` multiplied by x^shift into `target`, dropping anything past the end of `target`
+fn xor_shifted(target: &mut [u64], source: &[u64], shift: usize) {
+    let (words, bits) = (shift / 64, shift % 64);
+
+    for (i, word) in source.iter().enumerate() {
+        if let Some(t) = target.get_mut(i + words) {
+            *t ^= word << bits;
+        }
+        if bits != 0 {
+            if let Some(t) = target.get_mut(i + words + 1) {
+                *t ^= word >> (64 - bits);
+            }
+        }
+    }
+}
+
+// a system of linear equations over GF(2), kept in row echelon form as equations are added. each
+// stored equation is keyed by its lowest set coefficient (its pivot)
+pub struct LinearSystem {
+    unknowns: usize,
+    pivots: Vec<Option<(Vec<u64>, bool)>>,
+    rank: usize,
+    consistent: bool,
+}
+
+impl LinearSystem {
+    pub fn new(unknowns: usize) -> Self {
+        Self {
+            unknowns,
+            pivots: vec![None; unknowns],
+            rank: 0,
+            consistent: true,
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    pub fn is_consistent(&self) -> bool {
+        self.consistent
+    }
+
+    // adds the equation sum(coefficients[i] * x_i) = value, returning whether it told us anything
+    // new
+    pub fn add_equation(&mut self, mut coefficients: Vec<u64>, mut value: bool) -> bool {
+        let mut start = 0;
+
+        loop {
+            let pivot = match (start..coefficients.len()).find(|i| coefficients[*i] != 0) {
+                Some(i) => {
+                    start = i;
+                    i * 64 + coefficients[i].trailing_zeros() as usize
+                }
+                None => {
+                    self.consistent &= !value;
+                    return false;
+                }
+            };
+
+            match &self.pivots[pivot] {
+                Some((row, row_value)) => {
+                    for (word, r) in coefficients[start..].iter_mut().zip(&row[start..]) {
+                        *word ^= r;
+                    }
+                    value ^= row_value;
+                }
+                None => {
+                    self.pivots[pivot] = Some((coefficients, value));
+                    self.rank += 1;
+                    return true;
+                }
+            }
+        }
+    }
+
+    // back-substitutes for the unknowns if the system has exactly one solution
+    pub fn solve(&self) -> Option<Vec<u64>> {
+        if !self.consistent || self.rank < self.unknowns {
+            return None;
+        }
+
+        let mut solution = vec![0u64; self.unknowns / 64 + 1];
+        for (i, pivot) in self.pivots.iter().enumerate().rev() {
+            let (row, value) = pivot.as_ref()?;
+            let sum = row
+                .iter()
+                .zip(&solution)
+                .fold(0, |acc, (r, s)| acc ^ (r & s).count_ones())
+                & 1;
+
+            if (sum == 1) != *value {
+                solution[i / 64] |= 1 << (i % 64);
+            }
+        }
+
+        Some(solution)
+    }
+}
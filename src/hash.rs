@@ -0,0 +1,27 @@
+// the interface HMAC and friends need from a hash function
+pub trait Hash: Clone {
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> Self;
+    fn update(&mut self, message: &[u8]);
+    fn finalize(self) -> Vec<u8>;
+
+    fn digest(message: &[u8]) -> Vec<u8> {
+        let mut hash = Self::new();
+        hash.update(message);
+        hash.finalize()
+    }
+}
+
+// a Merkle-Damgard hash whose digest is its whole internal state, so that hashing can carry on
+// from a digest as if the message behind it were known
+pub trait LengthExtendable: Hash {
+    // the padding appended to a message of `message_length` bytes
+    fn padding(message_length: u64) -> Vec<u8>;
+
+    // resumes hashing from a digest, as if `length` bytes (a multiple of the block size) had
+    // already been processed. gives None for anything short of a whole state, such as a truncated
+    // digest
+    fn from_digest(digest: &[u8], length: u64) -> Option<Self>;
+}
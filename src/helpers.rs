@@ -173,7 +173,7 @@ impl From<&[u8]> for Base64 {
                 let mask = 0b00111111;
                 let inner_group_1 = (outer_group & (mask << 10)) >> 10;
                 let inner_group_2 = (outer_group & (mask << 4)) >> 4;
-                let inner_group_3 = (outer_group & mask) << 2;
+                let inner_group_3 = (outer_group & 0b1111) << 2;
 
                 v.push(get_ascii_value(inner_group_1));
                 v.push(get_ascii_value(inner_group_2));
@@ -184,9 +184,8 @@ impl From<&[u8]> for Base64 {
             v
         };
 
-        let mut output_bytes: Vec<u8> = bytes
+        let mut output_bytes: Vec<u8> = bytes[..length - (length % 3)]
             .chunks(3 as usize)
-            .take(length - (length % 3))
             .flat_map(|slice| get_inner_groups(slice))
             .collect();
 
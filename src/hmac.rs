@@ -0,0 +1,149 @@
+use crate::hash::Hash;
+
+// HMAC as specified in RFC 2104: H((K ^ opad) || H((K ^ ipad) || message))
+#[derive(Clone)]
+pub struct Hmac<H: Hash> {
+    inner: H,
+    outer: H,
+}
+
+impl<H: Hash> Hmac<H> {
+    const IPAD: u8 = 0x36;
+    const OPAD: u8 = 0x5c;
+
+    pub fn new(key: &[u8]) -> Self {
+        // keys longer than a block are hashed first, and every key is zero-padded to a block
+        let mut block = if key.len() > H::BLOCK_SIZE {
+            H::digest(key)
+        } else {
+            key.to_vec()
+        };
+        block.resize(H::BLOCK_SIZE, 0);
+
+        let pad = |byte: u8| -> H {
+            let mut hash = H::new();
+            hash.update(&block.iter().map(|k| k ^ byte).collect::<Vec<u8>>());
+            hash
+        };
+
+        Self {
+            inner: pad(Self::IPAD),
+            outer: pad(Self::OPAD),
+        }
+    }
+
+    pub fn mac<T>(key: &[u8], message: T) -> Vec<u8>
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut hmac = Self::new(key);
+        hmac.update(message);
+        hmac.finalize()
+    }
+
+    // checks a MAC without returning early, so the comparison doesn't leak how much of it was
+    // right
+    pub fn verify<T>(key: &[u8], message: T, mac: &[u8]) -> bool
+    where
+        T: AsRef<[u8]>,
+    {
+        let expected = Self::mac(key, message);
+
+        expected.len() == mac.len()
+            && expected
+                .iter()
+                .zip(mac)
+                .fold(0, |acc, (byte1, byte2)| acc | (byte1 ^ byte2))
+                == 0
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.inner.update(message.as_ref());
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = self.outer;
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+}
+
+#[test]
+fn test_hmac() {
+    use crate::helpers::Hex;
+    use crate::sha1::Sha1;
+    use crate::sha2::Sha256;
+
+    // test cases from RFC 2202 and RFC 4231
+    let cases: Vec<(Vec<u8>, &[u8], &str, &str)> = vec![
+        (
+            vec![0x0b; 20],
+            b"Hi There",
+            "b617318655057264e28bc0b6fb378c8ef146be00",
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        ),
+        (
+            b"Jefe".to_vec(),
+            b"what do ya want for nothing?",
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        ),
+        (
+            vec![0xaa; 20],
+            &[0xdd; 50],
+            "125d7342b9ac11cd91a39af48aa17b4f63f175d3",
+            "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+        ),
+        (
+            (1..=25).collect(),
+            &[0xcd; 50],
+            "4c9007f4026250c6bc8414f9bf50c86c2d7235da",
+            "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+        ),
+        (
+            vec![0xaa; 80],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            "aa4ae5e15272d00e95705637ce8a3b55ed402112",
+            "6953025ed96f0c09f80a96f78e6538dbe2e7b820e3dd970e7ddd39091b32352f",
+        ),
+        (
+            vec![0xaa; 80],
+            b"Test Using Larger Than Block-Size Key and Larger Than One Block-Size Data",
+            "e8e99d0f45237d786d6bbaa7965c7808bbff1a91",
+            "6355ac22e890d0a3c8481a5ca4825bc884d3e7a1ff98a2fc2ac7d8e064c3b2e6",
+        ),
+        (
+            vec![0xaa; 131],
+            b"This is a test using a larger than block-size key and a larger than block-size \
+              data. The key needs to be hashed before being used by the HMAC algorithm.",
+            "217e44bb08b6e06a2d6c30f3cb9f537f97c63356",
+            "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+        ),
+    ];
+
+    for (key, message, sha1_mac, sha256_mac) in cases {
+        assert_eq!(
+            Hex::from_bytes(&Hmac::<Sha1>::mac(&key, message)).to_string(),
+            sha1_mac
+        );
+        assert_eq!(
+            Hex::from_bytes(&Hmac::<Sha256>::mac(&key, message)).to_string(),
+            sha256_mac
+        );
+    }
+
+    let mac = Hmac::<Sha256>::mac(b"key", "email=foo@bar.com&uid=10&role=user");
+    assert!(Hmac::<Sha256>::verify(
+        b"key",
+        "email=foo@bar.com&uid=10&role=user",
+        &mac
+    ));
+    assert!(!Hmac::<Sha256>::verify(
+        b"key",
+        "email=foo@bar.com&uid=10&role=admin",
+        &mac
+    ));
+}
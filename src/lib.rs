@@ -9,8 +9,29 @@ extern crate rand;
 mod set1;
 mod set2;
 mod set3;
+mod set4;
+mod set5;
+mod set6;
+mod set7;
 
 // helpers
+mod biguint;
 mod block_ciphers;
+mod der;
+mod dh;
+mod dsa;
+mod gf2;
+mod hash;
 mod helpers;
+mod hmac;
+mod md4;
 mod mersenne_twister;
+mod network;
+mod primes;
+mod python_random;
+mod replay_cache;
+mod rsa;
+mod sha1;
+mod sha2;
+mod srp;
+mod toy_hash;
@@ -0,0 +1,201 @@
+use crate::hash::{Hash, LengthExtendable};
+use std::convert::TryFrom;
+
+// MD4 as specified in RFC 1320. like SHA-1, the internal state can be set directly
+#[derive(Clone)]
+pub struct Md4 {
+    state: [u32; 4],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Md4 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const OUTPUT_SIZE: usize = 16;
+    pub const INITIAL_STATE: [u32; 4] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    // resumes hashing from the given registers, as if `length` bytes (a multiple of the block
+    // size) had already been processed
+    pub fn from_state(state: [u32; 4], length: u64) -> Self {
+        assert!(
+            length.is_multiple_of(Self::BLOCK_SIZE as u64),
+            "the processed length must be a whole number of blocks"
+        );
+
+        Self {
+            state,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length,
+        }
+    }
+
+    // resumes hashing from a digest. MD4 writes its registers out little-endian
+    pub fn from_digest(digest: &[u8; Self::OUTPUT_SIZE], length: u64) -> Self {
+        let mut state = [0u32; 4];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        Self::from_state(state, length)
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut md4 = Self::new();
+        md4.update(message);
+        md4.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        for byte in message.as_ref() {
+            self.buffer.push(*byte);
+
+            if self.buffer.len() == Self::BLOCK_SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.compress(&block);
+                self.length += Self::BLOCK_SIZE as u64;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::OUTPUT_SIZE] {
+        let message_length = self.length + self.buffer.len() as u64;
+        self.update(padding(message_length));
+
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        for (chunk, word) in digest.chunks_mut(4).zip(&self.state) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut x = [0u32; 16];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            x[i] = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let f = |x: u32, y: u32, z: u32| (x & y) | (!x & z);
+        let g = |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z);
+        let h = |x: u32, y: u32, z: u32| x ^ y ^ z;
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+
+        // each round applies its function to the registers in the rotating order a, d, c, b
+        for i in 0..48 {
+            let (function, k, s) = match i / 16 {
+                0 => (f(b, c, d), i, [3, 7, 11, 19][i % 4]),
+                1 => (
+                    g(b, c, d).wrapping_add(0x5A827999),
+                    (i % 4) * 4 + (i % 16) / 4,
+                    [3, 5, 9, 13][i % 4],
+                ),
+                _ => (
+                    h(b, c, d).wrapping_add(0x6ED9EBA1),
+                    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15][i % 16],
+                    [3, 9, 11, 15][i % 4],
+                ),
+            };
+
+            let t = a.wrapping_add(function).wrapping_add(x[k]).rotate_left(s);
+            a = d;
+            d = c;
+            c = b;
+            b = t;
+        }
+
+        for (register, value) in self.state.iter_mut().zip(&[a, b, c, d]) {
+            *register = register.wrapping_add(*value);
+        }
+    }
+}
+
+impl Hash for Md4 {
+    const BLOCK_SIZE: usize = Md4::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Md4::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Md4::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Md4::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Md4::finalize(self).to_vec()
+    }
+}
+
+impl LengthExtendable for Md4 {
+    fn padding(message_length: u64) -> Vec<u8> {
+        padding(message_length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Option<Self> {
+        let digest = <&[u8; Md4::OUTPUT_SIZE]>::try_from(digest).ok()?;
+        Some(Md4::from_digest(digest, length))
+    }
+}
+
+// the Merkle-Damgard padding appended to a message of `message_length` bytes. it is the same as
+// SHA-1's, except that the length in bits is written as a little-endian u64
+pub fn padding(message_length: u64) -> Vec<u8> {
+    let zeroes =
+        (Md4::BLOCK_SIZE * 2 - 9 - (message_length as usize % Md4::BLOCK_SIZE)) % Md4::BLOCK_SIZE;
+
+    [
+        &[0x80][..],
+        &vec![0u8; zeroes],
+        &(message_length * 8).to_le_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_md4() {
+    use crate::helpers::Hex;
+
+    let hash = |message: &[u8]| Hex::from_bytes(&Md4::digest(message)).to_string();
+
+    // test suite from RFC 1320
+    assert_eq!(hash(b""), "31d6cfe0d16ae931b73c59d7e0c089c0");
+    assert_eq!(hash(b"a"), "bde52cb31de33e46245e05fbdbd6fb24");
+    assert_eq!(hash(b"abc"), "a448017aaf21d8525fc10ae87aa6729d");
+    assert_eq!(hash(b"message digest"), "d9130a8164549fe818874806e1c7014b");
+    assert_eq!(
+        hash(b"abcdefghijklmnopqrstuvwxyz"),
+        "d79e1c308aa5bbcdeea8ed63df412da9"
+    );
+    assert_eq!(
+        hash(b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789"),
+        "043f8582f241db351ce627e153e7f0e4"
+    );
+    assert_eq!(
+        hash(
+            b"1234567890123456789012345678901234567890123456789012345678901234567890123456\
+              7890"
+        ),
+        "e33b4ddc9c38f2199c3e7b164fcc0536"
+    );
+
+    // streaming in uneven pieces gives the same digest
+    let mut md4 = Md4::new();
+    for chunk in b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789".chunks(5) {
+        md4.update(chunk);
+    }
+    assert_eq!(
+        Hex::from_bytes(&md4.finalize()).to_string(),
+        "043f8582f241db351ce627e153e7f0e4"
+    );
+}
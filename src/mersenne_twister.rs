@@ -1,3 +1,23 @@
+use crate::gf2::{LinearSystem, Polynomial};
+
+lazy_static! {
+    // the characteristic polynomial of the generator's one-word state transition. it is recovered
+    // from the generator's own output, since a single output bit satisfies the same linear
+    // recurrence as the state
+    static ref CHARACTERISTIC_POLYNOMIAL: Polynomial = {
+        let mut mt = MersenneTwister::new(5489);
+        let sequence = (0..MersenneTwister::STATE_BITS * 2 / MersenneTwister::N + 1)
+            .flat_map(|_| {
+                mt.twist();
+                mt.inner.iter().map(|word| word & 1 == 1).collect::<Vec<bool>>()
+            })
+            .collect::<Vec<bool>>();
+
+        Polynomial::minimal_polynomial(&sequence)
+    };
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct MersenneTwister {
     index: usize,
     inner: [u64; Self::N as usize],
@@ -19,6 +39,9 @@ impl MersenneTwister {
     const F: u64 = 1812433253;
     const LOWER_MASK: u64 = (1 << Self::R) - 1;
     const UPPER_MASK: u64 = !Self::LOWER_MASK & (u32::MAX as u64);
+    const STATE_BITS: usize = Self::N * Self::W as usize - Self::R as usize;
+    // below this many twists it is cheaper to twist than to evaluate the jump polynomial
+    const JUMP_THRESHOLD: u64 = 1 << 15;
 
     pub fn new(seed: u64) -> Self {
         let index = Self::N;
@@ -33,6 +56,43 @@ impl MersenneTwister {
         Self { index, inner }
     }
 
+    // seeds the generator from an array of words, as init_by_array() does in the reference
+    // implementation. this is how most language runtimes (e.g. CPython) seed from large integers.
+    // an empty key is taken as [0], which is what CPython uses for a seed of 0
+    pub fn from_key(key: &[u32]) -> Self {
+        let key = if key.is_empty() { &[0][..] } else { key };
+        let mut mt = Self::new(19650218);
+        let (mut i, mut j) = (1, 0);
+
+        for _ in 0..Self::N.max(key.len()) {
+            let previous = mt.inner[i - 1] ^ (mt.inner[i - 1] >> 30);
+            mt.inner[i] = (u32::MAX as u64)
+                & ((mt.inner[i] ^ (previous * 1664525)) + key[j] as u64 + j as u64);
+            i += 1;
+            j = (j + 1) % key.len();
+
+            if i >= Self::N {
+                mt.inner[0] = mt.inner[Self::N - 1];
+                i = 1;
+            }
+        }
+
+        for _ in 0..Self::N - 1 {
+            let previous = mt.inner[i - 1] ^ (mt.inner[i - 1] >> 30);
+            mt.inner[i] = (u32::MAX as u64)
+                & ((mt.inner[i] ^ (previous * 1566083941)).wrapping_sub(i as u64));
+            i += 1;
+
+            if i >= Self::N {
+                mt.inner[0] = mt.inner[Self::N - 1];
+                i = 1;
+            }
+        }
+
+        mt.inner[0] = 1 << (Self::W - 1);
+        mt
+    }
+
     pub fn extract_number(&mut self) -> u64 {
         if self.index >= Self::N {
             if self.index > Self::N {
@@ -53,18 +113,412 @@ impl MersenneTwister {
         (u32::MAX as u64) & y
     }
 
+    // advances the generator as if `steps` numbers had been extracted
+    pub fn jump(&mut self, steps: u64) {
+        let position = self.index as u64 + steps;
+        let twists = position / Self::N as u64;
+
+        if twists < Self::JUMP_THRESHOLD {
+            for _ in 0..twists {
+                self.twist();
+            }
+        } else {
+            self.jump_twists(twists);
+        }
+
+        self.index = (position % Self::N as u64) as usize;
+    }
+
+    // moves the generator back as if the last `steps` extracted numbers had never been extracted
+    pub fn rewind(&mut self, steps: u64) {
+        if steps < self.index as u64 {
+            self.index -= steps as usize;
+        } else {
+            // rewinding onto a block boundary leaves the generator about to twist, which is also
+            // the state it is in right after seeding
+            let distance = steps - self.index as u64;
+            for _ in 0..distance / Self::N as u64 + 1 {
+                self.untwist();
+            }
+
+            self.index = Self::N - (distance % Self::N as u64) as usize;
+        }
+    }
+
     fn twist(&mut self) {
         for i in 0..Self::N {
-            let x = (self.inner[i] & Self::UPPER_MASK) + (self.inner[(i+1) % Self::N] & Self::LOWER_MASK);
-            let mut x_a = x >> 1;
+            self.inner[i] = Self::next_word(&self.inner, i);
+        }
+
+        self.index = 0;
+    }
+
+    // APPROACH
+    //
+    // twist computes inner[i] = inner[i + M] ^ twisted(x) where x is made of the upper bit of
+    // inner[i] and the lower bits of inner[i + 1]. walking backwards from the end of the state,
+    // inner[i + M] holds the same value it held when inner[i] was computed, so XORing it out
+    // leaves twisted(x). the top bit of twisted(x) is only set if A was XORed in, which tells us
+    // the low bit of x, so x can be recovered in full. x gives us the upper bit of the old
+    // inner[i] and the lower bits of the old inner[i + 1]. the lower bits of the old inner[0] are
+    // never used by twist, so they are lost (and don't matter).
+    //
+    // when rewinding over several twists, the lower bits of inner[0] are needed again, since this
+    // state was itself produced by a twist. they went into the x for the last position, so they
+    // can be put back first
+    fn untwist(&mut self) {
+        let recover_x = |inner: &[u64; Self::N], i: usize| -> u64 {
+            let mut x_a = inner[i] ^ inner[(i + Self::M as usize) % Self::N];
+            let odd = x_a & (1 << (Self::W - 1)) != 0;
 
-            if (x % 2) != 0 {
+            if odd {
                 x_a ^= Self::A;
             }
 
-            self.inner[i] = self.inner[(((i as u64)+Self::M) % (Self::N as u64)) as usize] ^ x_a;
+            (x_a << 1) | (odd as u64)
+        };
+
+        let x = recover_x(&self.inner, Self::N - 1);
+        self.inner[0] = (self.inner[0] & Self::UPPER_MASK) | (x & Self::LOWER_MASK);
+
+        for i in (0..Self::N).rev() {
+            let upper_bit = recover_x(&self.inner, i) & Self::UPPER_MASK;
+
+            // the lower bits of the old inner[i] are carried by the x that produced inner[i - 1],
+            // which is still intact because we are walking backwards
+            let lower_bits = if i == 0 {
+                0
+            } else {
+                recover_x(&self.inner, i - 1) & Self::LOWER_MASK
+            };
+
+            self.inner[i] = upper_bit | lower_bits;
         }
 
-        self.index = 0;
+        self.index = Self::N;
+    }
+
+    // the one-word state transition that twist applies to every position of the state in turn
+    fn next_word(inner: &[u64; Self::N], i: usize) -> u64 {
+        let x = (inner[i] & Self::UPPER_MASK) + (inner[(i + 1) % Self::N] & Self::LOWER_MASK);
+        let mut x_a = x >> 1;
+
+        if (x % 2) != 0 {
+            x_a ^= Self::A;
+        }
+
+        inner[(i + Self::M as usize) % Self::N] ^ x_a
+    }
+
+    // APPROACH
+    //
+    // the state transition S that computes one word is linear over GF(2), so if p(x) is its
+    // characteristic polynomial, S^n = (x^n mod p(x))(S) by Cayley-Hamilton. rather than applying
+    // S n times, we compute the remainder once and evaluate it at S, which costs about 19937
+    // applications of S no matter how large n is. twist is S applied N times.
+    //
+    // the lower bits of inner[0] that twist ignores sit outside of the part of the state that p(x)
+    // describes, so we twist once normally to flush them out before jumping the rest of the way
+    fn jump_twists(&mut self, twists: u64) {
+        self.twist();
+
+        let jump_polynomial = CHARACTERISTIC_POLYNOMIAL.x_pow_mod((twists - 1) * Self::N as u64);
+        let degree = jump_polynomial.degree().unwrap_or(0);
+
+        let mut jumped = [0u64; Self::N];
+        let mut current = self.inner;
+        let mut position = 0;
+
+        for i in 0..=degree {
+            if jump_polynomial.coefficient(i) {
+                for (j, word) in jumped.iter_mut().enumerate() {
+                    *word ^= current[(position + j) % Self::N];
+                }
+            }
+
+            current[position] = Self::next_word(&current, position);
+            position = (position + 1) % Self::N;
+        }
+
+        self.inner = jumped;
+    }
+}
+
+// a word of the generator's state in terms of a batch of 64 unknown state bits. lane k holds
+// which of the unknowns bit k of the word depends on
+type SymbolicWord = [u64; MersenneTwister::W as usize];
+
+// recovers the generator's state from outputs of which only some bits are known, e.g. because the
+// application only exposes rand() % 256 or the top bits of a float
+//
+// APPROACH
+//
+// every step of the generator is linear over GF(2), so each output bit is a fixed XOR of the
+// 19937 bits of the state. we find out which by running the generator symbolically, 64 unknowns
+// at a time, and every observed bit then gives us one linear equation. once the equations pin
+// down every unknown, the state falls out of Gaussian elimination
+//
+// the unknown state is the one right before the twist that produces the first observed output,
+// i.e. a freshly seeded generator or one that has just used up a block of N outputs. outputs that
+// weren't seen at all can be observed with an empty mask
+pub struct StateSolver {
+    observations: Vec<(u64, u64)>,
+}
+
+impl StateSolver {
+    pub fn new() -> Self {
+        Self {
+            observations: Vec::new(),
+        }
+    }
+
+    // records the next output of the generator, of which only the bits set in `mask` are known
+    pub fn observe(&mut self, output: u64, mask: u64) {
+        self.observations.push((output & mask, mask));
+    }
+
+    // returns a generator that continues from the last observed output, or None if the
+    // observations don't determine the state yet
+    pub fn solve(&self) -> Option<MersenneTwister> {
+        let equations = self.equations();
+        let mut system = LinearSystem::new(MersenneTwister::STATE_BITS);
+
+        for ((_, value), coefficients) in self.known_bits().zip(equations) {
+            system.add_equation(coefficients, value);
+        }
+
+        let solution = system.solve()?;
+        let mut mt = MersenneTwister {
+            index: MersenneTwister::N,
+            inner: [0u64; MersenneTwister::N],
+        };
+        for bit in 0..MersenneTwister::STATE_BITS {
+            if (solution[bit / 64] >> (bit % 64)) & 1 == 1 {
+                let (word, position) = Self::locate(bit);
+                mt.inner[word] |= 1 << position;
+            }
+        }
+
+        mt.jump(self.observations.len() as u64);
+        Some(mt)
+    }
+
+    // the known output bits as (output index, value) pairs, in the order they were observed
+    fn known_bits(&self) -> impl Iterator<Item = ((usize, usize), bool)> + '_ {
+        self.observations
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (output, mask))| {
+                (0..MersenneTwister::W as usize)
+                    .filter(move |bit| (mask >> bit) & 1 == 1)
+                    .map(move |bit| ((i, bit), (output >> bit) & 1 == 1))
+            })
+    }
+
+    // the coefficients of the equation for every known output bit
+    fn equations(&self) -> Vec<Vec<u64>> {
+        let known_bits = self.known_bits().map(|(b, _)| b).collect::<Vec<_>>();
+        let batches = MersenneTwister::STATE_BITS / 64 + 1;
+        let mut equations = vec![vec![0u64; batches]; known_bits.len()];
+
+        for batch in 0..batches {
+            let mut inner = [[0u64; MersenneTwister::W as usize]; MersenneTwister::N];
+            for lane in 0..64 {
+                let bit = batch * 64 + lane;
+                if bit < MersenneTwister::STATE_BITS {
+                    let (word, position) = Self::locate(bit);
+                    inner[word][position] |= 1 << lane;
+                }
+            }
+
+            let mut tempered = Vec::with_capacity(self.observations.len());
+            for i in 0..self.observations.len() {
+                if i % MersenneTwister::N == 0 {
+                    Self::twist(&mut inner);
+                }
+
+                tempered.push(Self::temper(&inner[i % MersenneTwister::N]));
+            }
+
+            for (equation, (i, bit)) in equations.iter_mut().zip(&known_bits) {
+                equation[batch] = tempered[*i][*bit];
+            }
+        }
+
+        equations
+    }
+
+    // maps an unknown to the state bit it stands for. the unknowns are the upper bit of inner[0]
+    // followed by every bit of the remaining words
+    fn locate(bit: usize) -> (usize, usize) {
+        if bit == 0 {
+            (0, MersenneTwister::W as usize - 1)
+        } else {
+            (
+                (bit - 1) / MersenneTwister::W as usize + 1,
+                (bit - 1) % MersenneTwister::W as usize,
+            )
+        }
+    }
+
+    fn shift_right(word: &SymbolicWord, n: u64) -> SymbolicWord {
+        let mut shifted = [0u64; MersenneTwister::W as usize];
+        shifted[..(MersenneTwister::W - n) as usize].copy_from_slice(&word[n as usize..]);
+        shifted
+    }
+
+    fn shift_left(word: &SymbolicWord, n: u64) -> SymbolicWord {
+        let mut shifted = [0u64; MersenneTwister::W as usize];
+        shifted[n as usize..].copy_from_slice(&word[..(MersenneTwister::W - n) as usize]);
+        shifted
+    }
+
+    fn xor_masked(word: &mut SymbolicWord, other: &SymbolicWord, mask: u64) {
+        for (bit, (w, o)) in word.iter_mut().zip(other).enumerate() {
+            if (mask >> bit) & 1 == 1 {
+                *w ^= o;
+            }
+        }
+    }
+
+    fn twist(inner: &mut [SymbolicWord; MersenneTwister::N]) {
+        for i in 0..MersenneTwister::N {
+            let mut x = inner[(i + 1) % MersenneTwister::N];
+            x[MersenneTwister::W as usize - 1] = inner[i][MersenneTwister::W as usize - 1];
+
+            let mut x_a = Self::shift_right(&x, 1);
+            for (bit, lane) in x_a.iter_mut().enumerate() {
+                if (MersenneTwister::A >> bit) & 1 == 1 {
+                    *lane ^= x[0];
+                }
+            }
+
+            Self::xor_masked(
+                &mut x_a,
+                &inner[(i + MersenneTwister::M as usize) % MersenneTwister::N],
+                u32::MAX as u64,
+            );
+            inner[i] = x_a;
+        }
+    }
+
+    fn temper(word: &SymbolicWord) -> SymbolicWord {
+        let mut y = *word;
+        let shifted = Self::shift_right(&y, MersenneTwister::U);
+        Self::xor_masked(&mut y, &shifted, MersenneTwister::D);
+        let shifted = Self::shift_left(&y, MersenneTwister::S);
+        Self::xor_masked(&mut y, &shifted, MersenneTwister::B);
+        let shifted = Self::shift_left(&y, MersenneTwister::T);
+        Self::xor_masked(&mut y, &shifted, MersenneTwister::C);
+        let shifted = Self::shift_right(&y, MersenneTwister::L);
+        Self::xor_masked(&mut y, &shifted, u32::MAX as u64);
+
+        y
+    }
+}
+
+#[test]
+fn test_jump() {
+    let mut mt1 = MersenneTwister::new(5489);
+    let mut mt2 = mt1.clone();
+
+    for _ in 0..10000 {
+        mt1.extract_number();
+    }
+    mt2.jump(10000);
+    assert_eq!(mt1.extract_number(), mt2.extract_number());
+
+    // check the polynomial jump against plain twisting
+    let twists = 1000;
+    for _ in 0..twists {
+        mt1.twist();
     }
+    mt2.jump_twists(twists);
+    assert_eq!(mt1.inner, mt2.inner);
+
+    // jumps compose
+    let mut mt3 = MersenneTwister::new(42);
+    let mut mt4 = mt3.clone();
+    mt3.jump(1 << 40);
+    mt3.jump(12345);
+    mt4.jump((1 << 40) + 12345);
+    assert_eq!(
+        (0..5).map(|_| mt3.extract_number()).collect::<Vec<u64>>(),
+        (0..5).map(|_| mt4.extract_number()).collect::<Vec<u64>>()
+    );
+}
+
+#[test]
+fn test_from_key() {
+    // an empty key doesn't panic, and seeds the same way as [0]
+    assert_eq!(
+        MersenneTwister::from_key(&[]).inner,
+        MersenneTwister::from_key(&[0]).inner
+    );
+}
+
+#[test]
+fn test_rewind() {
+    let seed = 1_600_000_000;
+    let mut mt = MersenneTwister::new(seed);
+    let numbers: Vec<u64> = (0..5000).map(|_| mt.extract_number()).collect();
+
+    mt.rewind(3);
+    assert_eq!(mt.extract_number(), numbers[4997]);
+
+    // rewinding all the way back gives the seeded state, less the bits twist never reads
+    mt.rewind(4998);
+    let candidates = (seed - 100..seed + 100)
+        .filter(|candidate| {
+            let seeded = MersenneTwister::new(*candidate);
+            seeded.inner[1..] == mt.inner[1..]
+                && seeded.inner[0] & MersenneTwister::UPPER_MASK == mt.inner[0]
+        })
+        .collect::<Vec<u64>>();
+    assert_eq!(candidates, vec![seed]);
+    assert_eq!(
+        (0..5000).map(|_| mt.extract_number()).collect::<Vec<u64>>(),
+        numbers
+    );
+}
+
+#[test]
+fn test_state_solver_with_bytes() {
+    let mut target = MersenneTwister::new(rand::random::<u32>() as u64);
+    let mut solver = StateSolver::new();
+
+    // only the low byte of every output is seen, as with rand() % 256
+    for _ in 0..4000 {
+        solver.observe(target.extract_number() & 0xFF, 0xFF);
+    }
+
+    let mut clone = solver.solve().unwrap();
+    assert_eq!(
+        (0..1000).map(|_| clone.extract_number()).collect::<Vec<u64>>(),
+        (0..1000).map(|_| target.extract_number()).collect::<Vec<u64>>()
+    );
+}
+
+#[test]
+fn test_state_solver_with_floats() {
+    let mut target = MersenneTwister::new(rand::random::<u32>() as u64);
+    let mut solver = StateSolver::new();
+
+    // floats in [0, 1) are built the way CPython's random() builds them, from the top 27 bits of
+    // one output and the top 26 bits of the next
+    for _ in 0..700 {
+        let a = target.extract_number() >> 5;
+        let b = target.extract_number() >> 6;
+        let float = (a as f64 * 67108864.0 + b as f64) / 9007199254740992.0;
+
+        let bits = (float * 9007199254740992.0) as u64;
+        solver.observe((bits >> 26) << 5, 0xFFFFFFE0);
+        solver.observe((bits & 0x3FFFFFF) << 6, 0xFFFFFFC0);
+    }
+
+    let mut clone = solver.solve().unwrap();
+    assert_eq!(
+        (0..1000).map(|_| clone.extract_number()).collect::<Vec<u64>>(),
+        (0..1000).map(|_| target.extract_number()).collect::<Vec<u64>>()
+    );
 }
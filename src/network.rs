@@ -0,0 +1,171 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+// a simulated network for protocol attacks. alice and bob each run on their own thread and talk
+// through a router thread, which hands every message to an interceptor on its way. the
+// interceptor can pass it on as it is, rewrite it, or drop it, and everything that happens is
+// written to a transcript
+
+// how long a party waits for a message before giving up, so that a dropped message ends the run
+// instead of hanging it
+const TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    AliceToBob,
+    BobToAlice,
+}
+
+pub trait Interceptor<M> {
+    // the message to deliver in place of `message`, or None to drop it
+    fn intercept(&mut self, direction: Direction, message: M) -> Option<M>;
+}
+
+// a wire with nobody on it
+pub struct Passive;
+
+impl<M> Interceptor<M> for Passive {
+    fn intercept(&mut self, _: Direction, message: M) -> Option<M> {
+        Some(message)
+    }
+}
+
+impl<M, F> Interceptor<M> for F
+where
+    F: FnMut(Direction, M) -> Option<M>,
+{
+    fn intercept(&mut self, direction: Direction, message: M) -> Option<M> {
+        self(direction, message)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Entry<M> {
+    pub direction: Direction,
+    pub sent: M,
+    pub delivered: Option<M>,
+}
+
+// one party's end of the wire
+pub struct Endpoint<M> {
+    direction: Direction,
+    outbox: Sender<(Direction, M)>,
+    inbox: Receiver<M>,
+}
+
+impl<M> Endpoint<M> {
+    pub fn send(&self, message: M) -> Result<(), &'static str> {
+        self.outbox
+            .send((self.direction, message))
+            .map_err(|_| "the network is down")
+    }
+
+    pub fn receive(&self) -> Result<M, &'static str> {
+        self.inbox
+            .recv_timeout(TIMEOUT)
+            .map_err(|error| match error {
+                RecvTimeoutError::Timeout => "timed out waiting for a message",
+                RecvTimeoutError::Disconnected => "the network is down",
+            })
+    }
+}
+
+// runs `alice` and `bob` to completion with `interceptor` in the middle, and returns what each of
+// them returned along with the transcript
+pub fn run<M, A, B, RA, RB, I>(alice: A, bob: B, interceptor: &mut I) -> (RA, RB, Vec<Entry<M>>)
+where
+    M: Clone + Send,
+    A: FnOnce(Endpoint<M>) -> RA + Send,
+    B: FnOnce(Endpoint<M>) -> RB + Send,
+    RA: Send,
+    RB: Send,
+    I: Interceptor<M> + Send + ?Sized,
+{
+    let (outbox, router_inbox) = mpsc::channel();
+    let (to_alice, alice_inbox) = mpsc::channel();
+    let (to_bob, bob_inbox) = mpsc::channel();
+
+    let alice_endpoint = Endpoint {
+        direction: Direction::AliceToBob,
+        outbox: outbox.clone(),
+        inbox: alice_inbox,
+    };
+    let bob_endpoint = Endpoint {
+        direction: Direction::BobToAlice,
+        outbox,
+        inbox: bob_inbox,
+    };
+
+    thread::scope(|scope| {
+        // the router runs until both parties have finished and dropped their endpoints
+        let router = scope.spawn(move || {
+            let mut transcript = Vec::new();
+
+            for (direction, sent) in router_inbox {
+                let delivered = interceptor.intercept(direction, M::clone(&sent));
+                if let Some(message) = &delivered {
+                    let recipient = match direction {
+                        Direction::AliceToBob => &to_bob,
+                        Direction::BobToAlice => &to_alice,
+                    };
+
+                    // a party that has already finished doesn't need any more messages
+                    recipient.send(message.clone()).ok();
+                }
+
+                transcript.push(Entry {
+                    direction,
+                    sent,
+                    delivered,
+                });
+            }
+
+            transcript
+        });
+
+        let alice = scope.spawn(move || alice(alice_endpoint));
+        let bob = scope.spawn(move || bob(bob_endpoint));
+
+        (
+            alice.join().unwrap(),
+            bob.join().unwrap(),
+            router.join().unwrap(),
+        )
+    })
+}
+
+#[test]
+fn test_network() {
+    let ping = |endpoint: Endpoint<u32>| {
+        endpoint.send(1)?;
+        endpoint.receive()
+    };
+    let pong = |endpoint: Endpoint<u32>| {
+        let n = endpoint.receive()?;
+        endpoint.send(n + 1)?;
+        Ok::<u32, &'static str>(n)
+    };
+
+    let (alice, bob, transcript) = run(ping, pong, &mut Passive);
+    assert_eq!(alice, Ok(2));
+    assert_eq!(bob, Ok(1));
+    assert_eq!(transcript.len(), 2);
+    assert_eq!(transcript[0].direction, Direction::AliceToBob);
+    assert_eq!(transcript[1].delivered, Some(2));
+
+    // rewriting a message
+    let mut doubler = |_, n: u32| Some(n * 2);
+    let (alice, bob, transcript) = run(ping, pong, &mut doubler);
+    assert_eq!(alice, Ok(6));
+    assert_eq!(bob, Ok(2));
+    assert_eq!((transcript[1].sent, transcript[1].delivered), (3, Some(6)));
+
+    // dropping one
+    let mut blackhole = |_, _| None;
+    let (alice, bob, transcript) = run(ping, pong, &mut blackhole);
+    assert!(alice.is_err());
+    assert!(bob.is_err());
+    assert_eq!(transcript.len(), 1);
+    assert_eq!(transcript[0].delivered, None);
+}
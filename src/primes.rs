@@ -0,0 +1,145 @@
+use crate::biguint::BigUint;
+
+lazy_static! {
+    // the odd primes below 2048, by the sieve of Eratosthenes
+    static ref SMALL_PRIMES: Vec<u64> = {
+        const LIMIT: usize = 2048;
+
+        let mut composite = vec![false; LIMIT];
+        for i in 2..LIMIT {
+            if !composite[i] {
+                (i * i..LIMIT).step_by(i).for_each(|j| composite[j] = true);
+            }
+        }
+
+        (3..LIMIT as u64).filter(|i| !composite[*i as usize]).collect()
+    };
+}
+
+// how many Miller-Rabin rounds a random candidate of this size needs. a random composite is much
+// less likely to fool a round than a chosen one, and less likely the bigger it is, so this is only
+// for random_prime
+fn rounds(bits: usize) -> usize {
+    match bits {
+        0..=512 => 7,
+        513..=1024 => 4,
+        _ => 3,
+    }
+}
+
+// whether n (odd, and at least 5) passes Miller-Rabin for `rounds` random bases. write
+// n - 1 = 2^s * d with d odd: for a prime n, every base a has a^d = 1 or a^(2^r * d) = -1 for some
+// r < s, and a composite n fails that for at least three quarters of the bases
+pub fn miller_rabin(n: &BigUint, rounds: usize) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let s = (0..).find(|i| n_minus_one.bit(*i)).unwrap();
+    let d = &n_minus_one >> s;
+
+    (0..rounds).all(|_| {
+        // a random base in [2, n - 2]
+        let a = &BigUint::random_below(&(n - &BigUint::from(3))) + &BigUint::from(2);
+
+        let mut x = a.modpow(&d, n);
+        if x == one || x == n_minus_one {
+            return true;
+        }
+
+        for _ in 1..s {
+            x = &(&x * &x) % n;
+            if x == n_minus_one {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+// how many Miller-Rabin rounds a number that may have been chosen to fool the test needs. a
+// composite gets through each round with probability at most 1/4, so this is 2^-128 for any n
+const ADVERSARIAL_ROUNDS: usize = 64;
+
+// whether n is prime, for any n, including one picked by someone hoping for a false positive
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    if let Some(small) = n.to_u64().filter(|small| *small < 2048) {
+        return small == 2 || SMALL_PRIMES.contains(&small);
+    }
+
+    n.is_odd()
+        && SMALL_PRIMES.iter().all(|p| n.rem_u64(*p) != 0)
+        && miller_rabin(n, ADVERSARIAL_ROUNDS)
+}
+
+// a random prime of exactly `bits` bits (at least 16). candidates are walked upwards from a random
+// odd starting point, and the remainders of the starting point by the small primes are kept so
+// that each step only needs a few additions to rule most of them out
+pub fn random_prime(bits: usize) -> BigUint {
+    assert!(bits >= 16, "the prime must be at least 16 bits");
+
+    loop {
+        let mut start = BigUint::random(bits);
+        start.set_bit(bits - 1);
+        start.set_bit(0);
+
+        let remainders = SMALL_PRIMES
+            .iter()
+            .map(|p| start.rem_u64(*p))
+            .collect::<Vec<u64>>();
+
+        for offset in (0..1 << 16).step_by(2) {
+            let survives = SMALL_PRIMES
+                .iter()
+                .zip(&remainders)
+                .all(|(p, remainder)| (remainder + offset) % p != 0);
+            if !survives {
+                continue;
+            }
+
+            let candidate = &start + &BigUint::from(offset);
+            if candidate.bits() != bits {
+                break;
+            }
+
+            if miller_rabin(&candidate, rounds(bits)) {
+                return candidate;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_primes() {
+    use openssl::bn::{BigNum, BigNumContext};
+
+    let mut context = BigNumContext::new().unwrap();
+    let to_bignum = |n: &BigUint| BigNum::from_slice(&n.to_bytes_be()).unwrap();
+
+    // small numbers, with the Carmichael numbers that fool the Fermat test
+    let primes = (0..3000u64)
+        .filter(|n| is_probable_prime(&BigUint::from(*n)))
+        .collect::<Vec<u64>>();
+    assert_eq!(primes.len(), 430);
+    assert_eq!(&primes[..6], &[2, 3, 5, 7, 11, 13]);
+    for carmichael in [561, 41041, 825265, 321197185].iter() {
+        assert!(!is_probable_prime(&BigUint::from(*carmichael)));
+    }
+
+    // products of two large primes
+    let p = random_prime(256);
+    let q = random_prime(256);
+    assert_eq!(p.bits(), 256);
+    assert!(to_bignum(&p).is_prime(64, &mut context).unwrap());
+    assert!(is_probable_prime(&p));
+    assert!(!is_probable_prime(&(&p * &q)));
+
+    // and whatever openssl says about random odd numbers
+    for _ in 0..200 {
+        let mut n = BigUint::random(128);
+        n.set_bit(0);
+        assert_eq!(
+            is_probable_prime(&n),
+            to_bignum(&n).is_prime(64, &mut context).unwrap()
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use crate::mersenne_twister::MersenneTwister;
+use openssl::sha;
+
+// reproduces the values CPython's random module derives from its Mersenne Twister, so that a
+// generator state recovered from a Python service can be used to predict what it does next
+pub struct PyRandom {
+    mt: MersenneTwister,
+}
+
+impl PyRandom {
+    // random.seed(n) for a non-negative integer n
+    pub fn new(seed: u64) -> Self {
+        Self::from_integer_bytes(&seed.to_le_bytes())
+    }
+
+    // random.seed(s) for a bytes or str seed (str seeds are hashed as their UTF-8 encoding)
+    pub fn from_bytes(seed: &[u8]) -> Self {
+        // the seed becomes the big-endian integer of the seed followed by its SHA-512 digest
+        let mut integer = [seed, &sha::sha512(seed)[..]].concat();
+        integer.reverse();
+
+        Self::from_integer_bytes(&integer)
+    }
+
+    // random.random(): a float in [0, 1) with 53 random bits, made from the top 27 bits of one
+    // output and the top 26 bits of the next
+    pub fn random(&mut self) -> f64 {
+        let a = self.mt.extract_number() >> 5;
+        let b = self.mt.extract_number() >> 6;
+
+        (a as f64 * 67108864.0 + b as f64) * (1.0 / 9007199254740992.0)
+    }
+
+    // random.getrandbits(k) for k up to 128. outputs fill the result from the least significant
+    // word up, and the last output is shifted down to the bits that are left
+    pub fn getrandbits(&mut self, k: u32) -> u128 {
+        assert!(k <= 128, "only up to 128 bits are supported");
+
+        let mut bits = 0;
+        let mut remaining = k;
+        let mut shift = 0;
+
+        while remaining > 0 {
+            let mut word = self.mt.extract_number();
+            if remaining < 32 {
+                word >>= 32 - remaining;
+            }
+
+            bits |= (word as u128) << shift;
+            remaining = remaining.saturating_sub(32);
+            shift += 32;
+        }
+
+        bits
+    }
+
+    // random.randint(a, b): a uniform integer in [a, b], drawn by rejection sampling with the
+    // fewest bits that can represent the width of the range
+    pub fn randint(&mut self, a: i64, b: i64) -> i64 {
+        assert!(a <= b, "empty range for randint");
+
+        let width = (b as i128 - a as i128 + 1) as u128;
+        let k = 128 - width.leading_zeros();
+
+        loop {
+            let r = self.getrandbits(k);
+            if r < width {
+                return (a as i128 + r as i128) as i64;
+            }
+        }
+    }
+
+    // seeds from the absolute value of an integer given as little-endian bytes. CPython splits it
+    // into 32-bit words, dropping the most significant zero words
+    fn from_integer_bytes(bytes: &[u8]) -> Self {
+        let mut key = bytes
+            .chunks(4)
+            .map(|chunk| {
+                let mut word = [0u8; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(word)
+            })
+            .collect::<Vec<u32>>();
+
+        while key.len() > 1 && key.last() == Some(&0) {
+            key.pop();
+        }
+
+        Self {
+            mt: MersenneTwister::from_key(&key),
+        }
+    }
+}
+
+impl From<MersenneTwister> for PyRandom {
+    fn from(mt: MersenneTwister) -> Self {
+        Self { mt }
+    }
+}
+
+#[test]
+fn test_integer_seed() {
+    // expected values come from CPython 3.11's random.Random(1234)
+    let mut random = PyRandom::new(1234);
+
+    assert_eq!(
+        (0..3)
+            .map(|_| random.getrandbits(32))
+            .collect::<Vec<u128>>(),
+        vec![4150886329, 3342196574, 1892932127]
+    );
+    assert_eq!(random.random(), 0.11685051774599753);
+    assert_eq!(random.getrandbits(100), 1263097734879819045699660835653);
+    assert_eq!(random.getrandbits(7), 103);
+    assert_eq!(
+        (0..8).map(|_| random.randint(1, 6)).collect::<Vec<i64>>(),
+        vec![5, 1, 6, 6, 1, 1, 3, 2]
+    );
+    assert_eq!(random.randint(-1000, 1_000_000_000_000), 30139622970);
+
+    assert_eq!(PyRandom::new(0).random(), 0.8444218515250481);
+}
+
+#[test]
+fn test_bytes_seed() {
+    let mut random = PyRandom::from_bytes("correct horse battery staple".as_bytes());
+    assert_eq!(random.random(), 0.19238391386563958);
+    assert_eq!(random.getrandbits(64), 12845309976748963164);
+
+    assert_eq!(
+        PyRandom::from_bytes(b"\x00\x01").random(),
+        0.29193984183030475
+    );
+}
+
+#[test]
+fn test_predict_from_recovered_state() {
+    use crate::mersenne_twister::StateSolver;
+
+    // a service hands out random() floats, then a getrandbits(64) token we want to predict
+    let mut service = PyRandom::from_bytes(b"service secret");
+    let mut solver = StateSolver::new();
+
+    for _ in 0..700 {
+        let bits = (service.random() * 9007199254740992.0) as u64;
+        solver.observe((bits >> 26) << 5, 0xFFFFFFE0);
+        solver.observe((bits & 0x3FFFFFF) << 6, 0xFFFFFFC0);
+    }
+
+    let mut prediction = PyRandom::from(solver.solve().unwrap());
+    assert_eq!(prediction.getrandbits(64), service.getrandbits(64));
+    assert_eq!(prediction.randint(1, 1000), service.randint(1, 1000));
+}
@@ -0,0 +1,106 @@
+use crate::sha2::Sha256;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+// remembers which messages a service has already seen, by their SHA-256, so that it can refuse to
+// handle any of them twice. entries can be given a lifetime, after which the message is accepted
+// again, like a server that only keeps the last few hours of requests
+pub struct ReplayCache {
+    seen: HashSet<[u8; Sha256::OUTPUT_SIZE]>,
+    expiry: Option<Duration>,
+    inserted: Vec<(Instant, [u8; Sha256::OUTPUT_SIZE])>,
+}
+
+impl ReplayCache {
+    pub fn new() -> Self {
+        Self {
+            seen: HashSet::new(),
+            expiry: None,
+            inserted: Vec::new(),
+        }
+    }
+
+    pub fn with_expiry(expiry: Duration) -> Self {
+        Self {
+            expiry: Some(expiry),
+            ..Self::new()
+        }
+    }
+
+    // records the message, and says whether this is the first time it has been seen
+    pub fn insert(&mut self, message: &[u8]) -> bool {
+        self.insert_at(message, Instant::now())
+    }
+
+    // the same as insert, but at a given time rather than now
+    pub fn insert_at(&mut self, message: &[u8], now: Instant) -> bool {
+        self.expire(now);
+
+        let hash = Sha256::digest(message);
+        let first = self.seen.insert(hash);
+        if first {
+            self.inserted.push((now, hash));
+        }
+
+        first
+    }
+
+    // forgets every message that has been in the cache for longer than its lifetime as of `now`
+    pub fn expire(&mut self, now: Instant) {
+        if let Some(expiry) = self.expiry {
+            let expired = self
+                .inserted
+                .iter()
+                .take_while(|(time, _)| now.duration_since(*time) >= expiry)
+                .count();
+            for (_, hash) in self.inserted.drain(..expired) {
+                self.seen.remove(&hash);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+impl Default for ReplayCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[test]
+fn test_replay_cache() {
+    let mut cache = ReplayCache::new();
+    assert!(cache.insert(b"hello"));
+    assert!(cache.insert(b"world"));
+    assert!(!cache.insert(b"hello"));
+    assert_eq!(cache.len(), 2);
+
+    // the clock is passed in, so that nothing depends on how long the test takes to run
+    let start = Instant::now();
+    let at = |millis| start + Duration::from_millis(millis);
+
+    let mut cache = ReplayCache::with_expiry(Duration::from_secs(1));
+    assert!(cache.insert_at(b"hello", at(0)));
+    assert!(!cache.insert_at(b"hello", at(500)));
+    assert!(cache.insert_at(b"world", at(600)));
+    assert_eq!(cache.len(), 2);
+
+    // a second after it was first seen, hello is forgotten but world isn't
+    cache.expire(at(999));
+    assert_eq!(cache.len(), 2);
+    cache.expire(at(1000));
+    assert_eq!(cache.len(), 1);
+    assert!(cache.insert_at(b"hello", at(1000)));
+    assert!(!cache.insert_at(b"world", at(1500)));
+
+    cache.expire(at(1600));
+    assert_eq!(cache.len(), 1);
+    assert!(!cache.insert_at(b"hello", at(1600)));
+}
@@ -0,0 +1,528 @@
+use crate::biguint::BigUint;
+use crate::der::{self, Reader};
+use crate::primes;
+use crate::sha1::Sha1;
+use crate::sha2::Sha256;
+
+// RSA as in PKCS#1 (RFC 8017): textbook encryption and signatures on integers, the v1.5 encodings
+// that make them usable on messages, and the key file formats
+
+// rsaEncryption, 1.2.840.113549.1.1.1
+const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Digest {
+    Sha1,
+    Sha256,
+}
+
+impl Digest {
+    pub fn hash(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Digest::Sha1 => Sha1::digest(message).to_vec(),
+            Digest::Sha256 => Sha256::digest(message).to_vec(),
+        }
+    }
+
+    // the DER of the DigestInfo that a signature wraps around the hash, up to the hash itself
+    pub fn prefix(&self) -> &'static [u8] {
+        match self {
+            Digest::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04,
+                0x14,
+            ],
+            Digest::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x01, 0x05, 0x00, 0x04, 0x20,
+            ],
+        }
+    }
+}
+
+// PKCS#1 keys are the bare RSA key. PKCS#8 (and its public key counterpart, the X.509
+// SubjectPublicKeyInfo) wraps it up with an identifier that says it is an RSA key
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Pkcs1,
+    Pkcs8,
+}
+
+fn algorithm_identifier() -> Vec<u8> {
+    der::sequence(&[
+        der::encode(der::OBJECT_IDENTIFIER, RSA_ENCRYPTION),
+        der::encode(der::NULL, &[]),
+    ])
+}
+
+fn read_algorithm_identifier(reader: &mut Reader) -> Result<(), &'static str> {
+    let mut algorithm = reader.sequence()?;
+    if algorithm.read(der::OBJECT_IDENTIFIER)? != RSA_ENCRYPTION {
+        return Err("not an RSA key");
+    }
+    if !algorithm.is_empty() {
+        algorithm.read(der::NULL)?;
+    }
+
+    algorithm.finish()
+}
+
+// EMSA-PKCS1-v1_5: 00 01 FF ... FF 00 DigestInfo, filling the whole k bytes of the modulus
+pub fn signature_encoding(
+    digest: Digest,
+    message: &[u8],
+    k: usize,
+) -> Result<Vec<u8>, &'static str> {
+    let digest_info = [digest.prefix(), &digest.hash(message)].concat();
+    if k < digest_info.len() + 11 {
+        return Err("the key is too small for this digest");
+    }
+
+    Ok([
+        &[0x00, 0x01][..],
+        &vec![0xff; k - digest_info.len() - 3],
+        &[0x00],
+        &digest_info,
+    ]
+    .concat())
+}
+
+// how a signature is checked. strict verification rebuilds the whole encoding and compares, rather
+// than trying to parse what the signature decrypts to. sloppy verification parses it, the way a lot
+// of real implementations once did: it checks for 00 01, some FF bytes, 00 and the DigestInfo, and
+// then stops reading, so whatever comes after the hash is never looked at
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Verification {
+    Strict,
+    Sloppy,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PublicKey {
+    pub n: BigUint,
+    pub e: BigUint,
+}
+
+impl PublicKey {
+    // the length of the modulus in bytes, which is the length of every ciphertext and signature
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    pub fn encrypt_raw(&self, m: &BigUint) -> BigUint {
+        m.modpow(&self.e, &self.n)
+    }
+
+    // RSAES-PKCS1-v1_5: the message goes at the end of 00 02 PS 00 M, where PS is at least eight
+    // random nonzero bytes
+    pub fn encrypt(&self, message: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let k = self.size();
+        if message.len() + 11 > k {
+            return Err("the message is too long for the key");
+        }
+
+        let padding = (0..k - message.len() - 3)
+            .map(|_| rand::random::<u8>() % 255 + 1)
+            .collect::<Vec<u8>>();
+        let encoded = [&[0x00, 0x02][..], &padding, &[0x00], message].concat();
+        let c = self.encrypt_raw(&BigUint::from_bytes_be(&encoded));
+
+        Ok(c.to_bytes_be_padded(k).unwrap())
+    }
+
+    pub fn verify(&self, digest: Digest, message: &[u8], signature: &[u8]) -> bool {
+        self.verify_with(Verification::Strict, digest, message, signature)
+    }
+
+    pub fn verify_with(
+        &self,
+        verification: Verification,
+        digest: Digest,
+        message: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        let k = self.size();
+        let s = BigUint::from_bytes_be(signature);
+        if signature.len() != k || s >= self.n {
+            return false;
+        }
+
+        let encoded = match self.encrypt_raw(&s).to_bytes_be_padded(k) {
+            Some(encoded) => encoded,
+            None => return false,
+        };
+
+        match verification {
+            Verification::Strict => match signature_encoding(digest, message, k) {
+                Ok(expected) => encoded == expected,
+                Err(_) => false,
+            },
+            Verification::Sloppy => {
+                let padding = encoded
+                    .iter()
+                    .skip(2)
+                    .take_while(|byte| **byte == 0xff)
+                    .count();
+                let rest = &encoded[2 + padding..];
+                let digest_info = [&[0x00], digest.prefix(), &digest.hash(message)].concat();
+
+                encoded[..2] == [0x00, 0x01] && padding > 0 && rest.starts_with(&digest_info)
+            }
+        }
+    }
+
+    pub fn to_der(&self, format: Format) -> Vec<u8> {
+        let pkcs1 = der::sequence(&[der::integer(&self.n), der::integer(&self.e)]);
+
+        match format {
+            Format::Pkcs1 => pkcs1,
+            Format::Pkcs8 => {
+                let bits = [&[0][..], &pkcs1].concat();
+                der::sequence(&[algorithm_identifier(), der::encode(der::BIT_STRING, &bits)])
+            }
+        }
+    }
+
+    pub fn from_der(bytes: &[u8], format: Format) -> Result<Self, &'static str> {
+        let mut outer = Reader::new(bytes);
+
+        let pkcs1 = match format {
+            Format::Pkcs1 => outer.read(der::SEQUENCE)?,
+            Format::Pkcs8 => {
+                let mut info = outer.sequence()?;
+                read_algorithm_identifier(&mut info)?;
+                let bits = info.read(der::BIT_STRING)?;
+                info.finish()?;
+
+                match bits.split_first() {
+                    Some((0, rest)) => Reader::new(rest).read(der::SEQUENCE)?,
+                    _ => return Err("invalid public key bit string"),
+                }
+            }
+        };
+        outer.finish()?;
+
+        let mut key = Reader::new(pkcs1);
+        let n = key.integer()?;
+        let e = key.integer()?;
+        key.finish()?;
+
+        Ok(Self { n, e })
+    }
+
+    pub fn to_pem(&self, format: Format) -> String {
+        let label = match format {
+            Format::Pkcs1 => "RSA PUBLIC KEY",
+            Format::Pkcs8 => "PUBLIC KEY",
+        };
+
+        der::pem_encode(label, &self.to_der(format))
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, &'static str> {
+        match der::pem_decode(pem)? {
+            (label, bytes) if label == "RSA PUBLIC KEY" => Self::from_der(&bytes, Format::Pkcs1),
+            (label, bytes) if label == "PUBLIC KEY" => Self::from_der(&bytes, Format::Pkcs8),
+            _ => Err("not an RSA public key"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrivateKey {
+    pub n: BigUint,
+    pub e: BigUint,
+    pub d: BigUint,
+    p: BigUint,
+    q: BigUint,
+    // d mod (p - 1), d mod (q - 1) and q^-1 mod p, for decrypting with the Chinese remainder
+    // theorem
+    dp: BigUint,
+    dq: BigUint,
+    q_inverse: BigUint,
+}
+
+impl PrivateKey {
+    // a key with a modulus of exactly `bits` bits, made of two primes of half that size for which
+    // e is a valid exponent
+    pub fn generate(bits: usize, e: u64) -> Self {
+        assert!(bits >= 64, "the modulus must be at least 64 bits");
+        assert!(e >= 3 && e % 2 == 1, "e must be odd and at least 3");
+
+        let e = BigUint::from(e);
+        let prime = |bits: usize| loop {
+            let p = primes::random_prime(bits);
+            if (&p - &BigUint::one()).gcd(&e) == BigUint::one() {
+                return p;
+            }
+        };
+
+        loop {
+            let p = prime(bits - bits / 2);
+            let q = prime(bits / 2);
+            if p == q || (&p * &q).bits() != bits {
+                continue;
+            }
+
+            if let Some(key) = Self::from_primes(&p, &q, &e) {
+                return key;
+            }
+        }
+    }
+
+    // d = e^-1 mod (p - 1)(q - 1), if there is one
+    pub fn from_primes(p: &BigUint, q: &BigUint, e: &BigUint) -> Option<Self> {
+        let one = BigUint::one();
+        let (p_minus_one, q_minus_one) = (p - &one, q - &one);
+        let d = e.modinv(&(&p_minus_one * &q_minus_one))?;
+
+        Some(Self {
+            n: p * q,
+            e: e.clone(),
+            dp: &d % &p_minus_one,
+            dq: &d % &q_minus_one,
+            q_inverse: q.modinv(p)?,
+            d,
+            p: p.clone(),
+            q: q.clone(),
+        })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey {
+            n: self.n.clone(),
+            e: self.e.clone(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    // c^d mod n, worked out mod p and mod q (with exponents a quarter of the size, for about a
+    // quarter of the work) and put back together with Garner's formula
+    pub fn decrypt_raw(&self, c: &BigUint) -> BigUint {
+        let m1 = c.modpow(&self.dp, &self.p);
+        let m2 = c.modpow(&self.dq, &self.q);
+        let h = &(&self.q_inverse * &(&(&m1 + &self.p) - &(&m2 % &self.p))) % &self.p;
+
+        &m2 + &(&h * &self.q)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let k = self.size();
+        let c = BigUint::from_bytes_be(ciphertext);
+        if ciphertext.len() != k || c >= self.n {
+            return Err("decryption error");
+        }
+
+        let encoded = self.decrypt_raw(&c).to_bytes_be_padded(k).unwrap();
+        let separator = encoded
+            .iter()
+            .skip(2)
+            .position(|byte| *byte == 0)
+            .map(|i| i + 2);
+
+        match separator {
+            Some(i) if encoded[..2] == [0x00, 0x02] && i >= 10 => Ok(encoded[i + 1..].to_vec()),
+            _ => Err("decryption error"),
+        }
+    }
+
+    pub fn sign(&self, digest: Digest, message: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let k = self.size();
+        let encoded = signature_encoding(digest, message, k)?;
+        let s = self.decrypt_raw(&BigUint::from_bytes_be(&encoded));
+
+        Ok(s.to_bytes_be_padded(k).unwrap())
+    }
+
+    pub fn to_der(&self, format: Format) -> Vec<u8> {
+        let pkcs1 = der::sequence(&[
+            der::integer(&BigUint::zero()),
+            der::integer(&self.n),
+            der::integer(&self.e),
+            der::integer(&self.d),
+            der::integer(&self.p),
+            der::integer(&self.q),
+            der::integer(&self.dp),
+            der::integer(&self.dq),
+            der::integer(&self.q_inverse),
+        ]);
+
+        match format {
+            Format::Pkcs1 => pkcs1,
+            Format::Pkcs8 => der::sequence(&[
+                der::integer(&BigUint::zero()),
+                algorithm_identifier(),
+                der::encode(der::OCTET_STRING, &pkcs1),
+            ]),
+        }
+    }
+
+    pub fn from_der(bytes: &[u8], format: Format) -> Result<Self, &'static str> {
+        let mut outer = Reader::new(bytes);
+
+        let pkcs1 = match format {
+            Format::Pkcs1 => outer.read(der::SEQUENCE)?,
+            Format::Pkcs8 => {
+                let mut info = outer.sequence()?;
+                if !info.integer()?.is_zero() {
+                    return Err("unsupported PKCS#8 version");
+                }
+                read_algorithm_identifier(&mut info)?;
+                let private_key = info.read(der::OCTET_STRING)?;
+
+                // any attributes after the key are ignored
+                Reader::new(private_key).read(der::SEQUENCE)?
+            }
+        };
+        outer.finish()?;
+
+        let mut key = Reader::new(pkcs1);
+        if !key.integer()?.is_zero() {
+            return Err("unsupported RSA private key version");
+        }
+
+        let key = Self {
+            n: key.integer()?,
+            e: key.integer()?,
+            d: key.integer()?,
+            p: key.integer()?,
+            q: key.integer()?,
+            dp: key.integer()?,
+            dq: key.integer()?,
+            q_inverse: key.integer()?,
+        };
+        if &key.p * &key.q != key.n {
+            return Err("the primes don't match the modulus");
+        }
+
+        Ok(key)
+    }
+
+    pub fn to_pem(&self, format: Format) -> String {
+        let label = match format {
+            Format::Pkcs1 => "RSA PRIVATE KEY",
+            Format::Pkcs8 => "PRIVATE KEY",
+        };
+
+        der::pem_encode(label, &self.to_der(format))
+    }
+
+    pub fn from_pem(pem: &str) -> Result<Self, &'static str> {
+        match der::pem_decode(pem)? {
+            (label, bytes) if label == "RSA PRIVATE KEY" => Self::from_der(&bytes, Format::Pkcs1),
+            (label, bytes) if label == "PRIVATE KEY" => Self::from_der(&bytes, Format::Pkcs8),
+            _ => Err("not an RSA private key"),
+        }
+    }
+}
+
+#[cfg(test)]
+fn to_openssl(key: &PrivateKey) -> openssl::rsa::Rsa<openssl::pkey::Private> {
+    openssl::rsa::Rsa::private_key_from_pem(key.to_pem(Format::Pkcs1).as_bytes()).unwrap()
+}
+
+#[test]
+fn test_keys() {
+    let key = PrivateKey::generate(1024, 65537);
+    assert_eq!(key.n.bits(), 1024);
+    assert_eq!(key.size(), 128);
+
+    // the CRT shortcut gives the same answer as the long way round
+    let m = BigUint::random_below(&key.n);
+    let c = key.public_key().encrypt_raw(&m);
+    assert_eq!(key.decrypt_raw(&c), m);
+    assert_eq!(c.modpow(&key.d, &key.n), m);
+
+    // a small e works too
+    let key = PrivateKey::generate(512, 3);
+    assert_eq!(key.e, BigUint::from(3));
+    let m = &m % &key.n;
+    assert_eq!(key.decrypt_raw(&key.public_key().encrypt_raw(&m)), m);
+
+    // and the key passes openssl's own consistency check
+    assert!(to_openssl(&key).check_key().unwrap());
+}
+
+#[test]
+fn test_pkcs1_v15() {
+    use openssl::hash::MessageDigest;
+    use openssl::pkey::PKey;
+    use openssl::rsa::Padding;
+    use openssl::sign::{Signer, Verifier};
+
+    let key = PrivateKey::generate(1024, 65537);
+    let public_key = key.public_key();
+    let openssl_key = to_openssl(&key);
+    let message = b"attack at dawn";
+
+    // encryption, both ways round
+    let ciphertext = public_key.encrypt(message).unwrap();
+    let mut decrypted = vec![0u8; key.size()];
+    let length = openssl_key
+        .private_decrypt(&ciphertext, &mut decrypted, Padding::PKCS1)
+        .unwrap();
+    assert_eq!(&decrypted[..length], message);
+
+    let mut ciphertext = vec![0u8; key.size()];
+    openssl_key
+        .public_encrypt(message, &mut ciphertext, Padding::PKCS1)
+        .unwrap();
+    assert_eq!(key.decrypt(&ciphertext).unwrap(), message);
+    assert!(public_key.encrypt(&[0u8; 118]).is_err());
+
+    // signatures, both ways round
+    let pkey = PKey::from_rsa(openssl_key).unwrap();
+    let signature = key.sign(Digest::Sha256, message).unwrap();
+    let mut verifier = Verifier::new(MessageDigest::sha256(), &pkey).unwrap();
+    verifier.update(message).unwrap();
+    assert!(verifier.verify(&signature).unwrap());
+
+    let mut signer = Signer::new(MessageDigest::sha1(), &pkey).unwrap();
+    signer.update(message).unwrap();
+    let signature = signer.sign_to_vec().unwrap();
+    assert!(public_key.verify(Digest::Sha1, message, &signature));
+    assert!(!public_key.verify(Digest::Sha256, message, &signature));
+    assert!(!public_key.verify(Digest::Sha1, b"attack at dusk", &signature));
+
+    // a sloppy verifier accepts genuine signatures too
+    let verify_sloppily = |message: &[u8]| {
+        public_key.verify_with(Verification::Sloppy, Digest::Sha1, message, &signature)
+    };
+    assert!(verify_sloppily(message));
+    assert!(!verify_sloppily(b"attack at dusk"));
+}
+
+#[test]
+fn test_key_formats() {
+    use openssl::pkey::PKey;
+    use openssl::rsa::Rsa;
+
+    let to_biguint = |n: &openssl::bn::BigNumRef| BigUint::from_bytes_be(&n.to_vec());
+
+    // a key from openssl, in every format
+    let rsa = Rsa::generate(2048).unwrap();
+    let pkey = PKey::from_rsa(rsa.clone()).unwrap();
+    let pkcs1 = String::from_utf8(rsa.private_key_to_pem().unwrap()).unwrap();
+    let pkcs8 = String::from_utf8(pkey.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+    let key = PrivateKey::from_pem(&pkcs1).unwrap();
+    assert_eq!(PrivateKey::from_pem(&pkcs8).unwrap(), key);
+    assert_eq!(key.n, to_biguint(rsa.n()));
+    assert_eq!(key.d, to_biguint(rsa.d()));
+    assert_eq!(key.to_pem(Format::Pkcs1), pkcs1);
+    assert_eq!(key.to_pem(Format::Pkcs8), pkcs8);
+
+    let public_pkcs1 = String::from_utf8(rsa.public_key_to_pem_pkcs1().unwrap()).unwrap();
+    let public_pkcs8 = String::from_utf8(rsa.public_key_to_pem().unwrap()).unwrap();
+    let public_key = PublicKey::from_pem(&public_pkcs1).unwrap();
+    assert_eq!(public_key, key.public_key());
+    assert_eq!(PublicKey::from_pem(&public_pkcs8).unwrap(), public_key);
+    assert_eq!(public_key.to_pem(Format::Pkcs1), public_pkcs1);
+    assert_eq!(public_key.to_pem(Format::Pkcs8), public_pkcs8);
+
+    // the wrong kind of key is refused
+    assert!(PrivateKey::from_pem(&public_pkcs8).is_err());
+    assert!(PublicKey::from_pem(&pkcs1).is_err());
+}
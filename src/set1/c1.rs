@@ -42,4 +42,12 @@ fn verify() {
             .to_bytes(),
         "my name is ethan".as_bytes()
     );
+
+    // every length of input, including the ones that end in a partial group
+    for length in 0..8 {
+        let bytes = (0..length).map(|i| 0xf0 | i as u8).collect::<Vec<u8>>();
+        let base64 = Base64::from(bytes.as_slice()).to_string();
+        assert_eq!(base64, openssl::base64::encode_block(&bytes));
+        assert_eq!(Base64::try_from(base64.as_str()).unwrap().to_bytes(), bytes);
+    }
 }
@@ -0,0 +1,23 @@
+use crate::sha1::Sha1;
+
+pub fn sha1_mac(key: &[u8], message: &[u8]) -> [u8; Sha1::OUTPUT_SIZE] {
+    Sha1::digest([key, message].concat())
+}
+
+#[test]
+fn verify() {
+    let key = b"YELLOW SUBMARINE";
+    let message = b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+    let mac = sha1_mac(key, message);
+
+    // tampering with the message changes the MAC
+    let mut tampered = message.to_vec();
+    tampered[32] ^= 1;
+    assert_ne!(mac, sha1_mac(key, &tampered));
+
+    // so does not knowing the key
+    assert_ne!(mac, sha1_mac(b"YELLOW SUBMARINF", message));
+    assert_ne!(mac, Sha1::digest(message));
+
+    assert_eq!(mac, sha1_mac(key, message));
+}
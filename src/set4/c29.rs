@@ -0,0 +1,124 @@
+use crate::hash::{Hash, LengthExtendable};
+use crate::sha1::Sha1;
+use rand::{distributions::Uniform, Rng};
+use std::marker::PhantomData;
+
+// signs cookies with a secret-prefix MAC, H(key || message), under any hash
+pub(super) struct Oracle<H> {
+    key: Vec<u8>,
+    hash: PhantomData<H>,
+}
+
+impl<H: Hash> Oracle<H> {
+    const ADMIN_ROLE_IDENTIFIER: &'static str = "admin=true";
+    const COOKIE: &'static [u8] =
+        b"comment1=cooking%20MCs;userdata=foo;comment2=%20like%20a%20pound%20of%20bacon";
+
+    pub(super) fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let key_length = rng.sample(Uniform::new(8, 33));
+        let key = (0..key_length).map(|_| rand::random::<u8>()).collect();
+
+        Oracle {
+            key,
+            hash: PhantomData,
+        }
+    }
+
+    fn mac(&self, message: &[u8]) -> Vec<u8> {
+        H::digest(&[&self.key, message].concat())
+    }
+
+    pub(super) fn cookie(&self) -> (Vec<u8>, Vec<u8>) {
+        (Self::COOKIE.to_vec(), self.mac(Self::COOKIE))
+    }
+
+    pub(super) fn is_admin(&self, cookie: &[u8], mac: &[u8]) -> bool {
+        if self.mac(cookie) != mac {
+            return false;
+        }
+
+        let data = String::from_utf8_lossy(cookie);
+        data.split(';')
+            .any(|field| field == Self::ADMIN_ROLE_IDENTIFIER)
+    }
+}
+
+// APPROACH
+//
+// a SHA-1 digest is the full internal state of the hash after the last block of the padded
+// message. if we set the registers to the MAC, we can keep hashing as if we were the holder of the
+// key, as long as the message we present includes the padding the original hash appended (the
+// "glue"). the glue encodes the length of key || message, so we try every key length until the
+// verifier accepts. nothing here is specific to SHA-1, so any hash that can be resumed from its
+// digest will do
+pub fn forge<H, F>(
+    mac: &[u8],
+    message: &[u8],
+    extension: &[u8],
+    mut verify: F,
+) -> Option<(Vec<u8>, Vec<u8>)>
+where
+    H: LengthExtendable,
+    F: FnMut(&[u8], &[u8]) -> bool,
+{
+    const MAX_KEY_LENGTH: usize = 128;
+
+    (0..=MAX_KEY_LENGTH).find_map(|key_length| {
+        let glue = H::padding((key_length + message.len()) as u64);
+        let forged_message = [message, &glue, extension].concat();
+
+        let processed_length = (key_length + message.len() + glue.len()) as u64;
+        let mut hash = H::from_digest(mac, processed_length)?;
+        hash.update(extension);
+        let forged_mac = hash.finalize();
+
+        if verify(&forged_message, &forged_mac) {
+            Some((forged_message, forged_mac))
+        } else {
+            None
+        }
+    })
+}
+
+#[test]
+fn test_sha2() {
+    use crate::sha2::{Sha256, Sha384, Sha512};
+
+    // SHA-256 gives away its whole state just like SHA-1
+    let oracle = Oracle::<Sha256>::new();
+    let (cookie, mac) = oracle.cookie();
+    let (forged_cookie, forged_mac) =
+        forge::<Sha256, _>(&mac, &cookie, b";admin=true", |cookie, mac| {
+            oracle.is_admin(cookie, mac)
+        })
+        .unwrap();
+    assert!(oracle.is_admin(&forged_cookie, &forged_mac));
+
+    // SHA-384 is SHA-512 with a different IV and a truncated digest. the missing 16 bytes of state
+    // leave nothing to resume from, so there is no forgery
+    let oracle = Oracle::<Sha384>::new();
+    let (cookie, mac) = oracle.cookie();
+    assert_eq!(
+        forge::<Sha512, _>(&mac, &cookie, b";admin=true", |cookie, mac| {
+            oracle.is_admin(cookie, mac)
+        }),
+        None
+    );
+}
+
+#[test]
+fn verify() {
+    let oracle = Oracle::<Sha1>::new();
+    let (cookie, mac) = oracle.cookie();
+    assert!(!oracle.is_admin(&cookie, &mac));
+
+    let (forged_cookie, forged_mac) =
+        forge::<Sha1, _>(&mac, &cookie, b";admin=true", |cookie, mac| {
+            oracle.is_admin(cookie, mac)
+        })
+        .unwrap();
+
+    assert!(forged_cookie.starts_with(&cookie));
+    assert!(oracle.is_admin(&forged_cookie, &forged_mac));
+}
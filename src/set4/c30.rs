@@ -0,0 +1,36 @@
+use super::c29;
+use crate::md4::Md4;
+
+// APPROACH
+//
+// the same attack as c29: the MAC is MD4's whole internal state, so we can load it into the
+// registers and keep hashing. the only difference is in the glue padding, which encodes the
+// length of key || message little-endian, and `Md4`'s `LengthExtendable` impl takes care of that
+pub fn forge_md4<F>(
+    mac: &[u8],
+    message: &[u8],
+    extension: &[u8],
+    verify: F,
+) -> Option<(Vec<u8>, Vec<u8>)>
+where
+    F: FnMut(&[u8], &[u8]) -> bool,
+{
+    c29::forge::<Md4, F>(mac, message, extension, verify)
+}
+
+#[test]
+fn verify() {
+    use super::c29::Oracle;
+
+    let oracle = Oracle::<Md4>::new();
+    let (cookie, mac) = oracle.cookie();
+    assert!(!oracle.is_admin(&cookie, &mac));
+
+    let (forged_cookie, forged_mac) = forge_md4(&mac, &cookie, b";admin=true", |cookie, mac| {
+        oracle.is_admin(cookie, mac)
+    })
+    .unwrap();
+
+    assert!(forged_cookie.starts_with(&cookie));
+    assert!(oracle.is_admin(&forged_cookie, &forged_mac));
+}
@@ -0,0 +1,283 @@
+use crate::helpers::Hex;
+use crate::hmac::Hmac;
+use crate::sha1::Sha1;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// a tiny HTTP server that checks ?file=...&signature=... against HMAC-SHA1(key, file), truncated
+// to `mac_length` bytes. the signature is compared a byte at a time with a sleep after every byte
+// that matches, and the comparison stops at the first byte that doesn't
+struct Server {
+    address: SocketAddr,
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    fn start(key: Vec<u8>, mac_length: usize, delay: Duration) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let running = Arc::new(AtomicBool::new(true));
+
+        let handle = {
+            let running = Arc::clone(&running);
+
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if !running.load(Ordering::SeqCst) {
+                        break;
+                    }
+
+                    if let Ok(stream) = stream {
+                        Self::respond(stream, &key, mac_length, delay);
+                    }
+                }
+            })
+        };
+
+        Self {
+            address,
+            running,
+            handle: Some(handle),
+        }
+    }
+
+    fn respond(mut stream: TcpStream, key: &[u8], mac_length: usize, delay: Duration) {
+        // read the whole request, so that closing the connection doesn't reset it before the
+        // client has read the response
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        if reader.read_line(&mut request_line).is_err() {
+            return;
+        }
+
+        let mut header = String::new();
+        while reader
+            .read_line(&mut header)
+            .map(|n| n > 2)
+            .unwrap_or(false)
+        {
+            header.clear();
+        }
+
+        let status = match Self::parse_query(&request_line) {
+            Some(query) => {
+                let file = query.get("file").cloned().unwrap_or_default();
+                let signature = query
+                    .get("signature")
+                    .and_then(|hex| Hex::try_from(hex.as_str()).ok())
+                    .map(|hex| hex.to_bytes())
+                    .unwrap_or_default();
+                let mut mac = Hmac::<Sha1>::mac(key, file);
+                mac.truncate(mac_length);
+
+                if Self::insecure_compare(&mac, &signature, delay) {
+                    "200 OK"
+                } else {
+                    "500 Internal Server Error"
+                }
+            }
+            None => "400 Bad Request",
+        };
+
+        let _ = write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+    }
+
+    // pulls the query parameters out of a request line like "GET /test?file=foo&signature=46b4
+    // HTTP/1.1"
+    fn parse_query(request_line: &str) -> Option<HashMap<String, String>> {
+        let target = request_line.split_whitespace().nth(1)?;
+        let (_, query) = target.split_once('?')?;
+
+        Some(
+            query
+                .split('&')
+                .filter_map(|pair| {
+                    let (name, value) = pair.split_once('=')?;
+                    Some((name.to_string(), value.to_string()))
+                })
+                .collect(),
+        )
+    }
+
+    fn insecure_compare(buffer1: &[u8], buffer2: &[u8], delay: Duration) -> bool {
+        if buffer1.len() != buffer2.len() {
+            return false;
+        }
+
+        for (byte1, byte2) in buffer1.iter().zip(buffer2) {
+            if byte1 != byte2 {
+                return false;
+            }
+
+            thread::sleep(delay);
+        }
+
+        true
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        // the listener only notices it should stop when the next connection comes in
+        self.running.store(false, Ordering::SeqCst);
+        let _ = TcpStream::connect(self.address);
+
+        if let Some(handle) = self.handle.take() {
+            handle.join().unwrap();
+        }
+    }
+}
+
+// sends a request for `file` with `signature`, returning whether the server accepted it and how
+// long it took to answer
+fn request(address: SocketAddr, file: &str, signature: &[u8]) -> (bool, Duration) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    stream.set_nodelay(true).unwrap();
+
+    let start = Instant::now();
+    write!(
+        stream,
+        "GET /test?file={}&signature={} HTTP/1.1\r\nHost: localhost\r\n\r\n",
+        file,
+        Hex::from_bytes(signature)
+    )
+    .unwrap();
+
+    let mut status_line = String::new();
+    BufReader::new(&stream).read_line(&mut status_line).unwrap();
+    let elapsed = start.elapsed();
+
+    (status_line.contains("200"), elapsed)
+}
+
+// the mean of the samples once the fastest and slowest quarter have been thrown out, so that the
+// occasional scheduling hiccup doesn't drown out the signal
+fn trimmed_mean(samples: &[Duration]) -> f64 {
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+
+    let trim = sorted.len() / 4;
+    let kept = &sorted[trim..sorted.len() - trim];
+
+    kept.iter().map(|d| d.as_secs_f64()).sum::<f64>() / kept.len() as f64
+}
+
+// times every candidate for byte i of the MAC, in rounds: every candidate is timed once, the best
+// eighth of them are timed more, and so on, each round ranking the candidates by the trimmed mean
+// of all of their timings so far. returns the winner, and how far it and the runner-up stand out
+// from the median candidate, in seconds
+fn time_byte(address: SocketAddr, file: &str, mac: &mut [u8], i: usize) -> (u8, f64, f64) {
+    const ROUNDS: [(usize, usize); 3] = [(256, 1), (32, 4), (4, 16)];
+
+    let mut timings: Vec<Vec<Duration>> = vec![Vec::new(); 256];
+    let mut candidates: Vec<u8> = (0..=255).collect();
+    let mut median = 0.0;
+
+    for (keep, samples) in ROUNDS.iter() {
+        candidates.truncate(*keep);
+
+        for _ in 0..*samples {
+            for candidate in &candidates {
+                mac[i] = *candidate;
+                timings[*candidate as usize].push(request(address, file, mac).1);
+            }
+        }
+
+        candidates.sort_by(|c1, c2| {
+            trimmed_mean(&timings[*c2 as usize])
+                .partial_cmp(&trimmed_mean(&timings[*c1 as usize]))
+                .unwrap()
+        });
+
+        if *keep == 256 {
+            median = trimmed_mean(&timings[candidates[128] as usize]);
+        }
+    }
+
+    let gap = |candidate: u8| trimmed_mean(&timings[candidate as usize]) - median;
+    (candidates[0], gap(candidates[0]), gap(candidates[1]))
+}
+
+// APPROACH
+//
+// the server takes one delay longer to reject a signature for every leading byte that is right.
+// so with the first i bytes of the MAC known, the candidate for byte i that takes the longest to
+// be rejected is very likely the right one. a single timing is too noisy to rely on when the
+// delay is only a few milliseconds, so each byte is timed in rounds (see `time_byte`).
+//
+// a wrong byte can still slip through now and then. when it does, every candidate for the next byte
+// is rejected just as quickly, so the winner is no further ahead of the runner-up than the
+// runner-up is of the rest, and it falls well short of the delay the earlier bytes stood out by.
+// that sends us back to time the previous byte again. the last byte doesn't need timing at all,
+// because the server tells us when the MAC is right, and if no last byte works, the one before it
+// goes back for another look too
+pub fn recover_mac(address: SocketAddr, file: &str, mac_length: usize) -> Option<Vec<u8>> {
+    if mac_length == 0 {
+        return None;
+    }
+
+    let max_backtracks = 2 * mac_length;
+
+    let mut mac = vec![0u8; mac_length];
+    // how far each accepted byte stood out, which together estimate the delay
+    let mut gaps: Vec<f64> = Vec::new();
+    let mut backtracks = 0;
+    let mut i = 0;
+
+    loop {
+        if i == mac_length - 1 {
+            let found = (0..=255).any(|byte| {
+                mac[i] = byte;
+                request(address, file, &mac).0
+            });
+            if found {
+                return Some(mac);
+            }
+        } else {
+            let (best, gap, runner_up) = time_byte(address, file, &mut mac, i);
+            let delay = gaps.iter().sum::<f64>() / gaps.len().max(1) as f64;
+
+            if gap - runner_up > runner_up && gap > delay / 2.0 {
+                mac[i] = best;
+                gaps.push(gap);
+                i += 1;
+                continue;
+            }
+        }
+
+        // nothing stood out, so the byte before this one is probably wrong. the first byte has
+        // nothing before it, and is simply timed again
+        backtracks += 1;
+        if backtracks > max_backtracks {
+            return None;
+        }
+        i = i.saturating_sub(1);
+        gaps.truncate(i);
+    }
+}
+
+#[test]
+fn verify() {
+    // a MAC truncated to 4 bytes and a 3ms delay keep the test short. the attack itself doesn't
+    // depend on either
+    let mac_length = 4;
+    let key = (0..16).map(|_| rand::random::<u8>()).collect::<Vec<u8>>();
+    let server = Server::start(key.clone(), mac_length, Duration::from_millis(3));
+    let file = "foo";
+
+    let (accepted, _) = request(server.address, file, &[0u8; 4]);
+    assert!(!accepted);
+
+    let mac = recover_mac(server.address, file, mac_length).unwrap();
+    assert_eq!(mac, Hmac::<Sha1>::mac(&key, file)[..mac_length].to_vec());
+
+    // there is nothing to recover from an empty MAC
+    assert_eq!(recover_mac(server.address, file, 0), None);
+}
@@ -0,0 +1,4 @@
+mod c28;
+mod c29;
+mod c30;
+mod c31;
@@ -0,0 +1,53 @@
+use crate::biguint::BigUint;
+use crate::dh::{self, Channel, Group};
+
+#[test]
+fn test_toy_group() {
+    let group = Group::toy();
+    let mut rng = rand::thread_rng();
+
+    let alice = group.generate_keypair(&mut rng);
+    let bob = group.generate_keypair(&mut rng);
+    let secret = alice.shared_secret(&group, &bob.public);
+
+    assert_eq!(secret, bob.shared_secret(&group, &alice.public));
+    assert!(secret < group.p);
+
+    // small enough to check by brute force: find alice's private key from her public key, then
+    // redo her half of the exchange with plain integers
+    let pow = |base: u64, exponent: u64| (0..exponent).fold(1, |acc, _| acc * base % 37);
+    let a = alice.public.to_u64().unwrap();
+    let x = (1..36).find(|x| pow(5, *x) == a).unwrap();
+    assert_eq!(
+        pow(bob.public.to_u64().unwrap(), x),
+        secret.to_u64().unwrap()
+    );
+}
+
+#[test]
+fn verify() {
+    let group = Group::modp_1536();
+    let mut rng = rand::thread_rng();
+
+    // alice and bob swap public keys in the clear...
+    let alice = group.generate_keypair(&mut rng);
+    let bob = group.generate_keypair(&mut rng);
+
+    // ...and end up with the same secret
+    let alice_secret = alice.shared_secret(&group, &bob.public);
+    let bob_secret = bob.shared_secret(&group, &alice.public);
+    assert_eq!(alice_secret, bob_secret);
+    assert_eq!(dh::derive_key(&alice_secret), dh::derive_key(&bob_secret));
+    assert_ne!(alice_secret, BigUint::one());
+
+    // which keys AES for the rest of the conversation
+    let mut alice_channel = Channel::new(&alice_secret);
+    let mut bob_channel = Channel::new(&bob_secret);
+
+    let message = alice_channel.seal("we attack at dawn");
+    let received = bob_channel.open(&message).unwrap();
+    assert_eq!(received, b"we attack at dawn");
+
+    let reply = bob_channel.seal(&received);
+    assert_eq!(alice_channel.open(&reply).unwrap(), received);
+}
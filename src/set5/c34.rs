@@ -0,0 +1,140 @@
+use crate::biguint::BigUint;
+use crate::dh::{Channel, Group};
+use crate::network::{self, Direction, Endpoint, Interceptor, Passive};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Parameters {
+        p: BigUint,
+        g: BigUint,
+        public: BigUint,
+    },
+    PublicKey(BigUint),
+    Data(Vec<u8>),
+}
+
+// alice picks the group, sends her half of the key exchange with it, and then sends `plaintext`
+// and expects to get it echoed back
+pub fn alice(
+    endpoint: Endpoint<Message>,
+    group: &Group,
+    plaintext: &[u8],
+) -> Result<(), &'static str> {
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+    endpoint.send(Message::Parameters {
+        p: group.p.clone(),
+        g: group.g.clone(),
+        public: keypair.public.clone(),
+    })?;
+
+    let bob_public = match endpoint.receive()? {
+        Message::PublicKey(public) => public,
+        _ => return Err("expected bob's public key"),
+    };
+    let mut channel = Channel::new(&keypair.shared_secret(group, &bob_public));
+
+    endpoint.send(Message::Data(channel.seal(plaintext)))?;
+    let echo = match endpoint.receive()? {
+        Message::Data(message) => channel.open(message)?,
+        _ => return Err("expected bob's reply"),
+    };
+
+    if echo == plaintext {
+        Ok(())
+    } else {
+        Err("bob didn't echo the message back")
+    }
+}
+
+// bob takes whatever group alice asks for, and echoes back the message she sends. he returns
+// the message so that we can check what he saw
+pub fn bob(endpoint: Endpoint<Message>) -> Result<Vec<u8>, &'static str> {
+    let (group, alice_public) = match endpoint.receive()? {
+        Message::Parameters { p, g, public } => (Group::new(p, g), public),
+        _ => return Err("expected alice's parameters"),
+    };
+
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+    endpoint.send(Message::PublicKey(keypair.public.clone()))?;
+    let mut channel = Channel::new(&keypair.shared_secret(&group, &alice_public));
+
+    let plaintext = match endpoint.receive()? {
+        Message::Data(message) => channel.open(message)?,
+        _ => return Err("expected alice's message"),
+    };
+    endpoint.send(Message::Data(channel.seal(&plaintext)))?;
+
+    Ok(plaintext)
+}
+
+// APPROACH
+//
+// neither side checks the public key it gets. mallory hands each of them p in place of the other's
+// public key, so they both compute p^x mod p = 0 as the shared secret. they can still talk to each
+// other, since they agree on the key, but mallory knows it too and reads everything as it goes by
+#[derive(Default)]
+pub struct Mallory {
+    p: Option<BigUint>,
+    pub plaintexts: Vec<Vec<u8>>,
+}
+
+impl Interceptor<Message> for Mallory {
+    fn intercept(&mut self, _: Direction, message: Message) -> Option<Message> {
+        match message {
+            Message::Parameters { p, g, .. } => {
+                self.p = Some(p.clone());
+                Some(Message::Parameters {
+                    public: p.clone(),
+                    p,
+                    g,
+                })
+            }
+            Message::PublicKey(_) => Some(Message::PublicKey(self.p.clone()?)),
+            Message::Data(ciphertext) => {
+                if let Ok(plaintext) = Channel::new(&BigUint::zero()).open(&ciphertext) {
+                    self.plaintexts.push(plaintext);
+                }
+                Some(Message::Data(ciphertext))
+            }
+        }
+    }
+}
+
+#[test]
+fn verify() {
+    let group = Group::modp_1536();
+    let plaintext = b"meet me at the usual place";
+
+    // with nobody in the middle, the echo goes through
+    let (alice_result, bob_result, transcript) = network::run(
+        |endpoint| alice(endpoint, &group, plaintext),
+        bob,
+        &mut Passive,
+    );
+    assert_eq!(alice_result, Ok(()));
+    assert_eq!(bob_result.unwrap(), plaintext);
+    assert_eq!(transcript.len(), 4);
+
+    // with mallory in the middle, it still does...
+    let mut mallory = Mallory::default();
+    let (alice_result, bob_result, transcript) = network::run(
+        |endpoint| alice(endpoint, &group, plaintext),
+        bob,
+        &mut mallory,
+    );
+    assert_eq!(alice_result, Ok(()));
+    assert_eq!(bob_result.unwrap(), plaintext);
+
+    // ...but she read both messages on the way
+    assert_eq!(
+        mallory.plaintexts,
+        vec![plaintext.to_vec(), plaintext.to_vec()]
+    );
+
+    // and the transcript shows the public keys she swapped out
+    let swapped = transcript
+        .iter()
+        .filter(|entry| Some(&entry.sent) != entry.delivered.as_ref())
+        .count();
+    assert_eq!(swapped, 2);
+}
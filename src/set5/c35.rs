@@ -0,0 +1,219 @@
+use crate::biguint::BigUint;
+use crate::dh::{Channel, Group};
+use crate::network::{self, Direction, Endpoint, Interceptor};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Negotiate { p: BigUint, g: BigUint },
+    Ack { p: BigUint, g: BigUint },
+    PublicKey(BigUint),
+    Data(Vec<u8>),
+}
+
+// alice proposes a group and uses whatever bob acknowledges, then does the key exchange and sends
+// `plaintext` to be echoed back
+pub fn alice(
+    endpoint: Endpoint<Message>,
+    group: &Group,
+    plaintext: &[u8],
+) -> Result<(), &'static str> {
+    endpoint.send(Message::Negotiate {
+        p: group.p.clone(),
+        g: group.g.clone(),
+    })?;
+
+    let group = match endpoint.receive()? {
+        Message::Ack { p, g } => Group::new(p, g),
+        _ => return Err("expected bob to acknowledge the group"),
+    };
+
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+    endpoint.send(Message::PublicKey(keypair.public.clone()))?;
+
+    let bob_public = match endpoint.receive()? {
+        Message::PublicKey(public) => public,
+        _ => return Err("expected bob's public key"),
+    };
+    let mut channel = Channel::new(&keypair.shared_secret(&group, &bob_public));
+
+    endpoint.send(Message::Data(channel.seal(plaintext)))?;
+    let echo = match endpoint.receive()? {
+        Message::Data(message) => channel.open(message)?,
+        _ => return Err("expected bob's reply"),
+    };
+
+    if echo == plaintext {
+        Ok(())
+    } else {
+        Err("bob didn't echo the message back")
+    }
+}
+
+// bob acknowledges whatever group alice proposes and echoes back her message
+pub fn bob(endpoint: Endpoint<Message>) -> Result<Vec<u8>, &'static str> {
+    let group = match endpoint.receive()? {
+        Message::Negotiate { p, g } => Group::new(p, g),
+        _ => return Err("expected alice to propose a group"),
+    };
+    endpoint.send(Message::Ack {
+        p: group.p.clone(),
+        g: group.g.clone(),
+    })?;
+
+    let alice_public = match endpoint.receive()? {
+        Message::PublicKey(public) => public,
+        _ => return Err("expected alice's public key"),
+    };
+    let keypair = group.generate_keypair(&mut rand::thread_rng());
+    endpoint.send(Message::PublicKey(keypair.public.clone()))?;
+    let mut channel = Channel::new(&keypair.shared_secret(&group, &alice_public));
+
+    let plaintext = match endpoint.receive()? {
+        Message::Data(message) => channel.open(message)?,
+        _ => return Err("expected alice's message"),
+    };
+    endpoint.send(Message::Data(channel.seal(&plaintext)))?;
+
+    Ok(plaintext)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MaliciousG {
+    One,
+    P,
+    PMinusOne,
+}
+
+// APPROACH
+//
+// bob acknowledges whatever group he is offered and alice trusts the acknowledgement, so mallory
+// can swap g out for a value of her choosing on the way to bob and both of them will use it. with
+// s = g^(ab) mod p:
+//
+// - g = 1 makes every power 1, so s = 1
+// - g = p makes every power 0, so s = 0
+// - g = p - 1 = -1 makes every power 1 or -1 depending on whether the exponent is even. the public
+//   keys give away whether a and b are odd, so s = p - 1 if both public keys are p - 1 and s = 1
+//   otherwise
+//
+// either way mallory knows the key, and reads the traffic
+pub struct Mallory {
+    g: MaliciousG,
+    p: Option<BigUint>,
+    publics: Vec<BigUint>,
+    pub plaintexts: Vec<Vec<u8>>,
+}
+
+impl Mallory {
+    pub fn new(g: MaliciousG) -> Self {
+        Self {
+            g,
+            p: None,
+            publics: Vec::new(),
+            plaintexts: Vec::new(),
+        }
+    }
+
+    pub fn predicted_secret(&self) -> Option<BigUint> {
+        let p = self.p.as_ref()?;
+
+        let secret = match self.g {
+            MaliciousG::One => BigUint::one(),
+            MaliciousG::P => BigUint::zero(),
+            MaliciousG::PMinusOne => {
+                let minus_one = p - &BigUint::one();
+                if self.publics.len() == 2 && self.publics.iter().all(|x| *x == minus_one) {
+                    minus_one
+                } else {
+                    BigUint::one()
+                }
+            }
+        };
+
+        Some(secret)
+    }
+}
+
+impl Interceptor<Message> for Mallory {
+    fn intercept(&mut self, _: Direction, message: Message) -> Option<Message> {
+        match message {
+            Message::Negotiate { p, .. } => {
+                let g = match self.g {
+                    MaliciousG::One => BigUint::one(),
+                    MaliciousG::P => p.clone(),
+                    MaliciousG::PMinusOne => &p - &BigUint::one(),
+                };
+                self.p = Some(p.clone());
+
+                Some(Message::Negotiate { p, g })
+            }
+            Message::PublicKey(public) => {
+                self.publics.push(public.clone());
+                Some(Message::PublicKey(public))
+            }
+            Message::Data(ciphertext) => {
+                let secret = self.predicted_secret()?;
+                if let Ok(plaintext) = Channel::new(&secret).open(&ciphertext) {
+                    self.plaintexts.push(plaintext);
+                }
+
+                Some(Message::Data(ciphertext))
+            }
+            ack => Some(ack),
+        }
+    }
+}
+
+#[test]
+fn verify() {
+    let group = Group::modp_1536();
+    let plaintext = b"the eagle has landed";
+    let minus_one = &group.p - &BigUint::one();
+
+    let attack = |g| {
+        let mut mallory = Mallory::new(g);
+        let (alice_result, bob_result, _) = network::run(
+            |endpoint| alice(endpoint, &group, plaintext),
+            bob,
+            &mut mallory,
+        );
+
+        // alice and bob don't notice anything...
+        assert_eq!(alice_result, Ok(()));
+        assert_eq!(bob_result.unwrap(), plaintext);
+
+        // ...but mallory reads both messages
+        assert_eq!(
+            mallory.plaintexts,
+            vec![plaintext.to_vec(), plaintext.to_vec()]
+        );
+
+        mallory
+    };
+
+    let mallory = attack(MaliciousG::One);
+    assert!(mallory.publics.iter().all(|x| *x == BigUint::one()));
+    assert_eq!(mallory.predicted_secret().unwrap(), BigUint::one());
+
+    let mallory = attack(MaliciousG::P);
+    assert!(mallory.publics.iter().all(|x| x.is_zero()));
+    assert_eq!(mallory.predicted_secret().unwrap(), BigUint::zero());
+
+    // both private keys are odd a quarter of the time, so keep going until we've seen both secrets
+    let (mut even, mut odd) = (false, false);
+    for _ in 0..64 {
+        let mallory = attack(MaliciousG::PMinusOne);
+        assert!(mallory
+            .publics
+            .iter()
+            .all(|x| *x == BigUint::one() || *x == minus_one));
+
+        let secret = mallory.predicted_secret().unwrap();
+        even |= secret == BigUint::one();
+        odd |= secret == minus_one;
+        if even && odd {
+            break;
+        }
+    }
+    assert!(even && odd);
+}
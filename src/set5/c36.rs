@@ -0,0 +1,124 @@
+use crate::biguint::BigUint;
+use crate::dh::Group;
+use crate::network::{self, Endpoint, Passive};
+use crate::srp::{self, Client, Parameters, Server, Verifier};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Hello { identity: String, public: BigUint },
+    Challenge { salt: Vec<u8>, public: BigUint },
+    Proof(Vec<u8>),
+    Verdict(bool),
+}
+
+// logs in as `identity` and returns whether the server let us in
+pub fn client(
+    endpoint: Endpoint<Message>,
+    parameters: &Parameters,
+    identity: &str,
+    password: &str,
+) -> Result<bool, &'static str> {
+    let client = Client::new(parameters);
+    endpoint.send(Message::Hello {
+        identity: identity.to_string(),
+        public: client.public.clone(),
+    })?;
+
+    let (salt, server_public) = match endpoint.receive()? {
+        Message::Challenge { salt, public } => (salt, public),
+        _ => return Err("expected a challenge"),
+    };
+    let key = client.session_key(identity, password, &salt, &server_public)?;
+    endpoint.send(Message::Proof(srp::proof(&key, &salt)))?;
+
+    match endpoint.receive()? {
+        Message::Verdict(verdict) => Ok(verdict),
+        _ => Err("expected a verdict"),
+    }
+}
+
+// runs a single login against the users in `verifiers` with a server made by `new_server`, and
+// returns who logged in
+pub fn server(
+    endpoint: Endpoint<Message>,
+    parameters: &Parameters,
+    verifiers: &HashMap<String, Verifier>,
+    new_server: fn(&Parameters, &Verifier) -> Server,
+) -> Result<String, &'static str> {
+    let (identity, client_public) = match endpoint.receive()? {
+        Message::Hello { identity, public } => (identity, public),
+        _ => return Err("expected a hello"),
+    };
+    let verifier = verifiers.get(&identity).ok_or("no such user")?;
+
+    let server = new_server(parameters, verifier);
+    endpoint.send(Message::Challenge {
+        salt: server.salt().to_vec(),
+        public: server.public.clone(),
+    })?;
+
+    let verdict = match endpoint.receive()? {
+        Message::Proof(proof) => server.verify(&client_public, &proof),
+        _ => return Err("expected a proof"),
+    };
+    endpoint.send(Message::Verdict(verdict))?;
+
+    if verdict {
+        Ok(identity)
+    } else {
+        Err("wrong password")
+    }
+}
+
+pub fn verifiers(parameters: &Parameters, users: &[(&str, &str)]) -> HashMap<String, Verifier> {
+    users
+        .iter()
+        .map(|(identity, password)| {
+            (
+                identity.to_string(),
+                Verifier::new(parameters, identity, password),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn verify() {
+    let parameters = Parameters::new(&Group::modp_2048());
+    let verifiers = verifiers(
+        &parameters,
+        &[
+            ("alice", "hunter2"),
+            ("bob", "correct horse battery staple"),
+        ],
+    );
+
+    let login = |identity: &'static str, password: &'static str| {
+        network::run(
+            |endpoint| client(endpoint, &parameters, identity, password),
+            |endpoint| server(endpoint, &parameters, &verifiers, Server::new),
+            &mut Passive,
+        )
+    };
+
+    // the right password gets in...
+    let (client_result, server_result, transcript) = login("alice", "hunter2");
+    assert_eq!(client_result, Ok(true));
+    assert_eq!(server_result, Ok("alice".to_string()));
+
+    // ...without ever crossing the wire
+    assert!(transcript.iter().all(|entry| match &entry.sent {
+        Message::Proof(proof) => !proof.windows(7).any(|window| window == b"hunter2"),
+        _ => true,
+    }));
+
+    let (client_result, server_result, _) = login("bob", "correct horse battery staple");
+    assert_eq!(client_result, Ok(true));
+    assert_eq!(server_result, Ok("bob".to_string()));
+
+    // the wrong one doesn't
+    let (client_result, server_result, _) = login("alice", "hunter3");
+    assert_eq!(client_result, Ok(false));
+    assert!(server_result.is_err());
+}
@@ -0,0 +1,60 @@
+use super::c36::{self, Message};
+use crate::biguint::BigUint;
+use crate::dh::Group;
+use crate::network::{self, Endpoint, Passive};
+use crate::srp::{self, Parameters, Server};
+
+// APPROACH
+//
+// the server computes S = (Av^u)^b mod N, and a client that sends a multiple of N as A makes that
+// 0 whatever v, u and b are. the client doesn't need the password to know that K = H(0), so it can
+// make a valid proof for any user. a server that checks A mod N != 0 is not fooled
+pub fn client(
+    endpoint: Endpoint<Message>,
+    parameters: &Parameters,
+    identity: &str,
+    multiple: u64,
+) -> Result<bool, &'static str> {
+    endpoint.send(Message::Hello {
+        identity: identity.to_string(),
+        public: &parameters.n * &BigUint::from(multiple),
+    })?;
+
+    let salt = match endpoint.receive()? {
+        Message::Challenge { salt, .. } => salt,
+        _ => return Err("expected a challenge"),
+    };
+    let key = srp::session_key(&BigUint::zero());
+    endpoint.send(Message::Proof(srp::proof(&key, &salt)))?;
+
+    match endpoint.receive()? {
+        Message::Verdict(verdict) => Ok(verdict),
+        _ => Err("expected a verdict"),
+    }
+}
+
+#[test]
+fn verify() {
+    let parameters = Parameters::new(&Group::modp_2048());
+    let verifiers = c36::verifiers(&parameters, &[("admin", "a password nobody will guess")]);
+
+    let login = |multiple, new_server| {
+        network::run(
+            |endpoint| client(endpoint, &parameters, "admin", multiple),
+            |endpoint| c36::server(endpoint, &parameters, &verifiers, new_server),
+            &mut Passive,
+        )
+    };
+
+    for multiple in 0..4 {
+        // A = 0, N, 2N, ... all get in without the password
+        let (client_result, server_result, _) = login(multiple, Server::naive);
+        assert_eq!(client_result, Ok(true));
+        assert_eq!(server_result, Ok("admin".to_string()));
+
+        // unless the server checks A
+        let (client_result, server_result, _) = login(multiple, Server::new);
+        assert_eq!(client_result, Ok(false));
+        assert!(server_result.is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::biguint::BigUint;
+use crate::dh::Group;
+use crate::network::{self, Endpoint, Passive};
+use crate::srp::{self, Verifier};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// simplified SRP: B = g^b with no multiple of v mixed in, and u is a random number sent along with
+// it instead of a hash of A and B. B no longer depends on the password, so a fake server can pick
+// it without knowing anything
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Hello {
+        identity: String,
+        public: BigUint,
+    },
+    Challenge {
+        salt: Vec<u8>,
+        public: BigUint,
+        u: BigUint,
+    },
+    Proof(Vec<u8>),
+    Verdict(bool),
+}
+
+// x = H(salt | password)
+fn private_key(salt: &[u8], password: &str) -> BigUint {
+    srp::hash(&[salt, password.as_bytes()])
+}
+
+pub fn verifier(group: &Group, password: &str) -> Verifier {
+    let salt = (0..16).map(|_| rand::random()).collect::<Vec<u8>>();
+    let x = private_key(&salt, password);
+
+    Verifier {
+        v: group.g.modpow(&x, &group.p),
+        salt,
+    }
+}
+
+// S = B^(a + ux) mod N
+pub fn client(
+    endpoint: Endpoint<Message>,
+    group: &Group,
+    identity: &str,
+    password: &str,
+) -> Result<bool, &'static str> {
+    let a = BigUint::random_below(&group.p);
+    endpoint.send(Message::Hello {
+        identity: identity.to_string(),
+        public: group.g.modpow(&a, &group.p),
+    })?;
+
+    let (salt, server_public, u) = match endpoint.receive()? {
+        Message::Challenge { salt, public, u } => (salt, public, u),
+        _ => return Err("expected a challenge"),
+    };
+    let x = private_key(&salt, password);
+    let secret = server_public.modpow(&(&a + &(&u * &x)), &group.p);
+    let key = srp::session_key(&secret);
+    endpoint.send(Message::Proof(srp::proof(&key, &salt)))?;
+
+    match endpoint.receive()? {
+        Message::Verdict(verdict) => Ok(verdict),
+        _ => Err("expected a verdict"),
+    }
+}
+
+// S = (Av^u)^b mod N
+pub fn server(
+    endpoint: Endpoint<Message>,
+    group: &Group,
+    verifiers: &HashMap<String, Verifier>,
+) -> Result<String, &'static str> {
+    let (identity, client_public) = match endpoint.receive()? {
+        Message::Hello { identity, public } => (identity, public),
+        _ => return Err("expected a hello"),
+    };
+    let Verifier { salt, v } = verifiers.get(&identity).ok_or("no such user")?;
+
+    let b = BigUint::random_below(&group.p);
+    let u = BigUint::random(128);
+    endpoint.send(Message::Challenge {
+        salt: salt.clone(),
+        public: group.g.modpow(&b, &group.p),
+        u: u.clone(),
+    })?;
+
+    let secret = (&(&client_public * &v.modpow(&u, &group.p)) % &group.p).modpow(&b, &group.p);
+    let key = srp::session_key(&secret);
+    let verdict = match endpoint.receive()? {
+        Message::Proof(proof) => srp::proof(&key, salt) == proof,
+        _ => return Err("expected a proof"),
+    };
+    endpoint.send(Message::Verdict(verdict))?;
+
+    if verdict {
+        Ok(identity)
+    } else {
+        Err("wrong password")
+    }
+}
+
+// everything a fake server needs to check password guesses offline
+#[derive(Clone, Debug)]
+pub struct Capture {
+    pub identity: String,
+    pub salt: Vec<u8>,
+    pub client_public: BigUint,
+    pub proof: Vec<u8>,
+}
+
+// APPROACH
+//
+// mallory poses as the server and picks b = 1 (so B = g), u = 1 and any salt. the client then
+// computes S = g^(a + x) = A * g^x mod N, and proves that it has H(S). A is public, so every guess
+// at the password gives a guess at S that can be checked against the proof with no more help from
+// the client
+pub fn mallory(endpoint: Endpoint<Message>, group: &Group) -> Result<Capture, &'static str> {
+    let (identity, client_public) = match endpoint.receive()? {
+        Message::Hello { identity, public } => (identity, public),
+        _ => return Err("expected a hello"),
+    };
+
+    let salt = (0..16).map(|_| rand::random()).collect::<Vec<u8>>();
+    endpoint.send(Message::Challenge {
+        salt: salt.clone(),
+        public: group.g.clone(),
+        u: BigUint::one(),
+    })?;
+
+    let proof = match endpoint.receive()? {
+        Message::Proof(proof) => proof,
+        _ => return Err("expected a proof"),
+    };
+
+    // turn the client away, as if it had mistyped the password
+    endpoint.send(Message::Verdict(false))?;
+
+    Ok(Capture {
+        identity,
+        salt,
+        client_public,
+        proof,
+    })
+}
+
+// how a run of the dictionary went
+#[derive(Clone, Debug)]
+pub struct Stats {
+    pub tried: usize,
+    pub elapsed: Duration,
+    pub threads: usize,
+}
+
+impl Stats {
+    // passwords per second
+    pub fn rate(&self) -> f64 {
+        self.tried as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+// tries every password in `wordlist` against what mallory captured, shared out between all of the
+// cores, and says how fast it went
+pub fn crack(group: &Group, capture: &Capture, wordlist: &[&str]) -> (Option<String>, Stats) {
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let chunk_size = wordlist.len().div_ceil(threads).max(1);
+
+    let found = AtomicBool::new(false);
+    let tried = AtomicUsize::new(0);
+    let start = Instant::now();
+
+    let password = thread::scope(|scope| {
+        let handles: Vec<_> = wordlist
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let (found, tried) = (&found, &tried);
+
+                scope.spawn(move || {
+                    for password in chunk {
+                        if found.load(Ordering::Relaxed) {
+                            return None;
+                        }
+                        tried.fetch_add(1, Ordering::Relaxed);
+
+                        let x = private_key(&capture.salt, password);
+                        let secret =
+                            &(&capture.client_public * &group.g.modpow(&x, &group.p)) % &group.p;
+                        let key = srp::session_key(&secret);
+
+                        if srp::proof(&key, &capture.salt) == capture.proof {
+                            found.store(true, Ordering::Relaxed);
+                            return Some(password.to_string());
+                        }
+                    }
+
+                    None
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .next()
+    });
+
+    let stats = Stats {
+        tried: tried.load(Ordering::Relaxed),
+        elapsed: start.elapsed(),
+        threads,
+    };
+
+    (password, stats)
+}
+
+#[test]
+fn test_simplified_srp() {
+    let group = Group::modp_2048();
+    let verifiers = [("alice".to_string(), verifier(&group, "hunter2"))]
+        .iter()
+        .cloned()
+        .collect::<HashMap<String, Verifier>>();
+
+    let login = |password: &'static str| {
+        network::run(
+            |endpoint| client(endpoint, &group, "alice", password),
+            |endpoint| server(endpoint, &group, &verifiers),
+            &mut Passive,
+        )
+    };
+
+    let (client_result, server_result, _) = login("hunter2");
+    assert_eq!(client_result, Ok(true));
+    assert_eq!(server_result, Ok("alice".to_string()));
+
+    let (client_result, server_result, _) = login("hunter3");
+    assert_eq!(client_result, Ok(false));
+    assert!(server_result.is_err());
+}
+
+#[test]
+fn verify() {
+    let group = Group::modp_2048();
+
+    let words = [
+        "password", "dragon", "monkey", "letmein", "sunshine", "shadow", "master", "princess",
+        "football", "baseball", "welcome", "trustno1", "qwerty", "iloveyou", "superman", "batman",
+    ];
+    let wordlist = words
+        .iter()
+        .flat_map(|word| {
+            (0..8).map(move |n| match n {
+                0 => word.to_string(),
+                n => format!("{}{}", word, n),
+            })
+        })
+        .collect::<Vec<String>>();
+    let wordlist = wordlist.iter().map(|word| &word[..]).collect::<Vec<&str>>();
+
+    let password = wordlist[rand::random::<usize>() % wordlist.len()];
+
+    // the client tries to log in, but it's mallory on the other end...
+    let (client_result, capture, _) = network::run(
+        |endpoint| client(endpoint, &group, "alice", password),
+        |endpoint| mallory(endpoint, &group),
+        &mut Passive,
+    );
+    assert_eq!(client_result, Ok(false));
+    let capture = capture.unwrap();
+    assert_eq!(capture.identity, "alice");
+
+    // ...who takes what she got and runs the dictionary against it
+    let (found, stats) = crack(&group, &capture, &wordlist);
+    assert_eq!(found.unwrap(), password);
+
+    // the search stops early, and on a single thread it stops right at the password
+    let position = wordlist.iter().position(|word| *word == password).unwrap();
+    assert!(stats.tried >= 1 && stats.tried <= wordlist.len());
+    assert!(stats.threads >= 1);
+    assert!(stats.rate() > 0.0);
+    if stats.threads == 1 {
+        assert_eq!(stats.tried, position + 1);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::biguint::{self, BigUint};
+use crate::rsa::{PrivateKey, PublicKey};
+
+// APPROACH
+//
+// the same m encrypted under e different keys with the same small e gives c_i = m^e mod n_i. the
+// Chinese remainder theorem turns those into m^e mod n_1 * ... * n_e, and m < n_i for every i
+// means m^e is smaller than that product. so the combined residue is m^e itself, with no
+// reduction, and an integer e-th root recovers m
+pub fn broadcast_attack(intercepted: &[(PublicKey, BigUint)]) -> Result<BigUint, &'static str> {
+    let e = match intercepted.first() {
+        Some((key, _)) => key.e.to_u64().ok_or("e is too big")?,
+        None => return Err("no ciphertexts"),
+    };
+    if e < 2 {
+        return Err("e must be at least 2");
+    }
+    if intercepted.iter().any(|(key, _)| key.e != BigUint::from(e)) {
+        return Err("the keys don't all have the same e");
+    }
+    if (intercepted.len() as u64) < e {
+        return Err("not enough ciphertexts for this e");
+    }
+
+    let congruences = intercepted[..e as usize]
+        .iter()
+        .map(|(key, c)| (c.clone(), key.n.clone()))
+        .collect::<Vec<_>>();
+    let power = biguint::chinese_remainder(&congruences).ok_or("the moduli share a factor")?;
+
+    let m = power.nth_root(e as u32);
+    if m.pow(e as u32) == power {
+        Ok(m)
+    } else {
+        Err("the ciphertexts aren't all of the same message")
+    }
+}
+
+#[cfg(test)]
+fn broadcast(message: &BigUint, bits: usize, e: u64) -> Vec<(PublicKey, BigUint)> {
+    (0..e)
+        .map(|_| {
+            let key = PrivateKey::generate(bits, e).public_key();
+            let c = key.encrypt_raw(message);
+            (key, c)
+        })
+        .collect()
+}
+
+#[test]
+fn test_small_e() {
+    let message = BigUint::from_bytes_be(b"the same thing, to everyone");
+
+    for e in [5, 7, 17].iter() {
+        let intercepted = broadcast(&message, 512, *e);
+        assert_eq!(broadcast_attack(&intercepted), Ok(message.clone()));
+
+        // one short is not enough
+        assert!(broadcast_attack(&intercepted[1..]).is_err());
+    }
+
+    // an e of 0 or 1 is no use either
+    for e in [0, 1].iter() {
+        let key = PublicKey {
+            n: BigUint::from(3233),
+            e: BigUint::from(*e),
+        };
+        assert!(broadcast_attack(&[(key, BigUint::from(42))]).is_err());
+    }
+
+    // nor are different messages
+    let mut intercepted = broadcast(&message, 512, 3);
+    intercepted[0].1 = intercepted[0].0.encrypt_raw(&BigUint::from(42));
+    assert!(broadcast_attack(&intercepted).is_err());
+}
+
+#[test]
+fn verify() {
+    let message = BigUint::from_bytes_be(b"meet me at the usual place at ten rather than eight");
+    let intercepted = broadcast(&message, 1024, 3);
+
+    assert_eq!(broadcast_attack(&intercepted), Ok(message));
+}
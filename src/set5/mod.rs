@@ -0,0 +1,7 @@
+mod c33;
+mod c34;
+mod c35;
+mod c36;
+mod c37;
+mod c38;
+mod c40;
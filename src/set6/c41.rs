@@ -0,0 +1,73 @@
+use crate::biguint::BigUint;
+use crate::replay_cache::ReplayCache;
+use crate::rsa::{PrivateKey, PublicKey};
+
+// a service that decrypts anything it is sent, but only once. anything it has already decrypted
+// (say, a message it has already passed on) is refused
+pub struct Server {
+    key: PrivateKey,
+    cache: ReplayCache,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self {
+            key: PrivateKey::generate(1024, 65537),
+            cache: ReplayCache::new(),
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.key.public_key()
+    }
+
+    pub fn decrypt(&mut self, ciphertext: &BigUint) -> Result<BigUint, &'static str> {
+        if ciphertext >= &self.key.n {
+            return Err("ciphertext out of range");
+        }
+        if !self.cache.insert(&ciphertext.to_bytes_be()) {
+            return Err("already decrypted");
+        }
+
+        Ok(self.key.decrypt_raw(ciphertext))
+    }
+}
+
+// APPROACH
+//
+// unpadded RSA is multiplicative: (s^e * c)^d = s * m mod n. so for a random s, c' = s^e * c is a
+// ciphertext the server has never seen, it happily decrypts it to s * m, and dividing by s (times
+// its inverse mod n) leaves m
+pub fn recover(server: &mut Server, ciphertext: &BigUint) -> Result<BigUint, &'static str> {
+    let key = server.public_key();
+
+    let (s, s_inverse) = loop {
+        let s = BigUint::random_below(&key.n);
+        if let Some(s_inverse) = s.modinv(&key.n) {
+            if s > BigUint::one() {
+                break (s, s_inverse);
+            }
+        }
+    };
+
+    let blinded = &(&key.encrypt_raw(&s) * ciphertext) % &key.n;
+    let product = server.decrypt(&blinded)?;
+
+    Ok(&(&product * &s_inverse) % &key.n)
+}
+
+#[test]
+fn verify() {
+    let mut server = Server::new();
+    let message = BigUint::from_bytes_be(b"{time: 1356304276, social: '555-55-5555'}");
+
+    // someone else's message goes through the server...
+    let ciphertext = server.public_key().encrypt_raw(&message);
+    assert_eq!(server.decrypt(&ciphertext), Ok(message.clone()));
+
+    // ...which won't decrypt it again
+    assert!(server.decrypt(&ciphertext).is_err());
+
+    // but will decrypt it in disguise
+    assert_eq!(recover(&mut server, &ciphertext), Ok(message));
+}
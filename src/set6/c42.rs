@@ -0,0 +1,70 @@
+use crate::biguint::BigUint;
+use crate::rsa::{Digest, PrivateKey, PublicKey, Verification};
+
+// APPROACH
+//
+// a sloppy verifier only reads 00 01 FF 00 DigestInfo off the front of s^3 mod n and ignores
+// everything after it. so put that block at the top of an otherwise blank k-byte number, fill the
+// rest with FF bytes, and take the integer cube root. the root cubed is a little below what we
+// asked for, but the difference only reaches into the bytes after the hash as long as there are
+// enough of them: roughly a third of the key has to be left over as garbage. s^3 is below n, so
+// it never wraps and no private key is needed
+pub fn forge(key: &PublicKey, digest: Digest, message: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if key.e != BigUint::from(3) {
+        return Err("the forgery only works for e = 3");
+    }
+
+    let k = key.size();
+    let block = [
+        &[0x00, 0x01, 0xff, 0x00][..],
+        digest.prefix(),
+        &digest.hash(message),
+    ]
+    .concat();
+    if block.len() > k {
+        return Err("the key is too small for this digest");
+    }
+
+    let target = [&block[..], &vec![0xff; k - block.len()]].concat();
+    let s = BigUint::from_bytes_be(&target).cbrt();
+
+    // the cube has to start with the block we wanted, or the garbage was too short
+    let cube = s
+        .pow(3)
+        .to_bytes_be_padded(k)
+        .ok_or("the forgery doesn't fit")?;
+    if !cube.starts_with(&block) {
+        return Err("the key is too small to leave room for the garbage");
+    }
+
+    s.to_bytes_be_padded(k).ok_or("the forgery doesn't fit")
+}
+
+#[test]
+fn test_digests() {
+    let key = PrivateKey::generate(2048, 3).public_key();
+    let message = b"hi mom";
+
+    for digest in [Digest::Sha1, Digest::Sha256].iter() {
+        let signature = forge(&key, *digest, message).unwrap();
+        assert!(key.verify_with(Verification::Sloppy, *digest, message, &signature));
+        assert!(!key.verify(*digest, message, &signature));
+    }
+
+    // e = 65537 keys are out of reach
+    let key = PrivateKey::generate(1024, 65537).public_key();
+    assert!(forge(&key, Digest::Sha1, message).is_err());
+}
+
+#[test]
+fn verify() {
+    let key = PrivateKey::generate(1024, 3).public_key();
+    let message = b"hi mom";
+
+    let signature = forge(&key, Digest::Sha1, message).unwrap();
+    assert!(key.verify_with(Verification::Sloppy, Digest::Sha1, message, &signature));
+
+    // a strict verifier sees the garbage and refuses
+    assert!(!key.verify(Digest::Sha1, message, &signature));
+    assert!(!key.verify_with(Verification::Strict, Digest::Sha1, message, &signature));
+}
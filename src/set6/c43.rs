@@ -0,0 +1,102 @@
+use crate::biguint::BigUint;
+use crate::dsa::{self, Parameters, PrivateKey, PublicKey, Signature};
+use crate::helpers::Hex;
+use crate::sha1::Sha1;
+use std::convert::TryFrom;
+
+// s = k^-1 (H(m) + xr) mod q, so anyone who knows k can solve for x = (sk - H(m)) r^-1 mod q
+pub fn private_key_from_nonce(
+    parameters: &Parameters,
+    hash: &BigUint,
+    signature: &Signature,
+    k: &BigUint,
+) -> Option<BigUint> {
+    let q = &parameters.q;
+    let r_inverse = signature.r.modinv(q)?;
+    let sk = &(&signature.s * k) % q;
+
+    Some(&(&(&(&sk + q) - &(hash % q)) * &r_inverse) % q)
+}
+
+// APPROACH
+//
+// a nonce that only ever takes one of a few values is as good as known. r = (g^k mod p) mod q
+// depends on k alone, so walk g^k up through the range, one multiplication by g at a time, until
+// it gives r. that k gives x, which is checked against y = g^x
+pub fn recover_from_nonce_range(
+    public_key: &PublicKey,
+    message: &[u8],
+    signature: &Signature,
+    range: u64,
+) -> Option<BigUint> {
+    let Parameters { p, q, g } = &public_key.parameters;
+    let hash = dsa::hash(message);
+
+    let mut g_k = BigUint::one();
+    for k in 0..range {
+        if &g_k % q == signature.r {
+            let k = BigUint::from(k);
+            let x = private_key_from_nonce(&public_key.parameters, &hash, signature, &k);
+            if let Some(x) = x.filter(|x| g.modpow(x, p) == public_key.y) {
+                return Some(x);
+            }
+        }
+
+        g_k = &(&g_k * g) % p;
+    }
+
+    None
+}
+
+#[test]
+fn test_nonce_range() {
+    let parameters = Parameters::cryptopals();
+    let key = PrivateKey::generate(&parameters);
+    let message = b"signed with a nonce that's far too small";
+
+    let k = BigUint::from(1 + rand::random::<u64>() % 0xffff);
+    let signature = key.sign_with_nonce(message, &k);
+
+    assert_eq!(
+        recover_from_nonce_range(&key.public_key(), message, &signature, 1 << 16),
+        Some(key.x)
+    );
+}
+
+#[test]
+fn verify() {
+    let parse = |hex: &str| BigUint::from(&Hex::try_from(hex).unwrap());
+    let public_key = PublicKey {
+        parameters: Parameters::cryptopals(),
+        y: parse(concat!(
+            "084ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4b",
+            "debf2955b4736012f21a08084056b19bcd7fee56048e004e44984e2f411788ef",
+            "dc837a0d2e5abb7b555039fd243ac01f0fb2ed1dec568280ce678e931868d23e",
+            "b095fde9d3779191b8c0299d6e07bbb283e6633451e535c45513b2d33c99ea17",
+        )),
+    };
+
+    let message = concat!(
+        "For those that envy a MC it can be hazardous to your health\n",
+        "So be friendly, a matter of life and death, just like a etch-a-sketch\n",
+    );
+    assert_eq!(
+        dsa::hash(message.as_bytes()),
+        parse("d2d0714f014a9784047eaeccf956520045c45265")
+    );
+
+    let signature = Signature {
+        // 548099063082341131477253921760299949438196259240 and
+        // 857042759984254168557880549501802188789837994940
+        r: parse("60019cacdc56eedf8e080984bfa898c8c5c419a8"),
+        s: parse("961f2062efc3c68db965a90c924cf76580ec1bbc"),
+    };
+
+    let x = recover_from_nonce_range(&public_key, message.as_bytes(), &signature, 1 << 16).unwrap();
+    assert_eq!(
+        Sha1::digest(Hex::from(&x).to_string()).to_vec(),
+        Hex::try_from("0954edd5e0afe5542a4adf012611a91912a3ec16")
+            .unwrap()
+            .to_bytes()
+    );
+}
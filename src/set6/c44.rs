@@ -0,0 +1,105 @@
+use super::c43;
+use crate::biguint::BigUint;
+use crate::dsa::{self, Parameters, PrivateKey, PublicKey, Signature};
+use std::collections::HashMap;
+
+// one signed message from a log of them
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub message: Vec<u8>,
+    pub signature: Signature,
+}
+
+// APPROACH
+//
+// r only depends on k, so two signatures with the same r used the same nonce. subtracting
+// s1 = k^-1 (H(m1) + xr) from s2 = k^-1 (H(m2) + xr) gets rid of x, leaving
+// k = (H(m1) - H(m2)) / (s1 - s2) mod q, and k gives x. scanning the log for a repeated r finds
+// the pairs, and a candidate x is checked against y before it is believed
+pub fn recover_from_repeated_nonce(public_key: &PublicKey, log: &[Entry]) -> Option<BigUint> {
+    let Parameters { p, q, g } = &public_key.parameters;
+    let difference = |a: &BigUint, b: &BigUint| &(&(a % q) + q) - &(b % q);
+
+    let mut seen: HashMap<Vec<u8>, &Entry> = HashMap::new();
+    for entry in log {
+        let r = entry.signature.r.to_bytes_be();
+        let earlier = match seen.get(&r) {
+            Some(earlier) => earlier,
+            None => {
+                seen.insert(r, entry);
+                continue;
+            }
+        };
+
+        let hashes = (dsa::hash(&earlier.message), dsa::hash(&entry.message));
+        let s_inverse = match difference(&earlier.signature.s, &entry.signature.s).modinv(q) {
+            Some(s_inverse) => s_inverse,
+            None => continue,
+        };
+        let k = &(&difference(&hashes.0, &hashes.1) * &s_inverse) % q;
+
+        let x =
+            c43::private_key_from_nonce(&public_key.parameters, &hashes.1, &entry.signature, &k);
+        if let Some(x) = x.filter(|x| g.modpow(x, p) == public_key.y) {
+            return Some(x);
+        }
+    }
+
+    None
+}
+
+// a signer whose random number generator keeps coming up with the same few nonces
+#[cfg(test)]
+fn sign_all(key: &PrivateKey, messages: &[&str], nonces: &[BigUint]) -> Vec<Entry> {
+    messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| Entry {
+            message: message.as_bytes().to_vec(),
+            signature: key.sign_with_nonce(message.as_bytes(), &nonces[i % nonces.len()]),
+        })
+        .collect()
+}
+
+#[test]
+fn test_fresh_nonces() {
+    let parameters = Parameters::cryptopals();
+    let key = PrivateKey::generate(&parameters);
+    let messages = ["one", "two", "three", "four"];
+    let log = messages
+        .iter()
+        .map(|message| Entry {
+            message: message.as_bytes().to_vec(),
+            signature: key.sign(message.as_bytes()).unwrap(),
+        })
+        .collect::<Vec<Entry>>();
+
+    assert_eq!(recover_from_repeated_nonce(&key.public_key(), &log), None);
+}
+
+#[test]
+fn verify() {
+    let parameters = Parameters::cryptopals();
+    let key = PrivateKey::generate(&parameters);
+
+    let messages = [
+        "transfer 10 to bob",
+        "transfer 20 to carol",
+        "transfer 30 to dave",
+        "rotate the backup keys",
+        "transfer 40 to erin",
+        "close the account",
+        "open a new account",
+    ];
+    let nonces = (0..5)
+        .map(|_| BigUint::random_below(&parameters.q))
+        .collect::<Vec<BigUint>>();
+    let log = sign_all(&key, &messages, &nonces);
+
+    // five nonces between seven signatures, so two of them are used twice
+    assert_eq!(log[0].signature.r, log[5].signature.r);
+    assert_eq!(
+        recover_from_repeated_nonce(&key.public_key(), &log),
+        Some(key.x)
+    );
+}
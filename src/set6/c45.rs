@@ -0,0 +1,94 @@
+use crate::biguint::BigUint;
+use crate::dsa::{Parameters, PrivateKey, PublicKey, Signature};
+
+// the same parameters, with g swapped for something else
+pub fn tamper(parameters: &Parameters, g: BigUint) -> Parameters {
+    Parameters {
+        g,
+        ..parameters.clone()
+    }
+}
+
+// APPROACH
+//
+// with g = 0, r = (0^k mod p) mod q = 0 whatever k is, and a verifier computes
+// v = (0^u1 * y^u2 mod p) mod q = 0 too. so (0, anything invertible) passes for every message, as
+// long as the verifier doesn't insist on 0 < r
+pub fn magic_signature_zero() -> Signature {
+    Signature {
+        r: BigUint::zero(),
+        s: BigUint::one(),
+    }
+}
+
+// APPROACH
+//
+// with g = p + 1, every power of g is 1 mod p, so v = (y^u2 mod p) mod q with u2 = r/s. picking
+// any z and setting r = (y^z mod p) mod q and s = r/z makes u2 = z, so v = r. unlike g = 0 this
+// signature is in range, and it works for every message at once, because H(m) only ever appears
+// in the exponent of g
+pub fn magic_signature_p_plus_one(public_key: &PublicKey) -> Signature {
+    let Parameters { p, q, .. } = &public_key.parameters;
+
+    loop {
+        let z = BigUint::random_below(q);
+        let z_inverse = match z.modinv(q) {
+            Some(z_inverse) => z_inverse,
+            None => continue,
+        };
+
+        let r = &public_key.y.modpow(&z, p) % q;
+        let s = &(&r * &z_inverse) % q;
+        if !r.is_zero() && !s.is_zero() {
+            return Signature { r, s };
+        }
+    }
+}
+
+#[test]
+fn test_zero_g() {
+    let parameters = Parameters::cryptopals();
+    let key = PrivateKey::generate(&tamper(&parameters, BigUint::zero()));
+    assert!(!key.parameters.is_valid());
+
+    // a key made with g = 0 signs everything with r = 0, so an honest signer refuses rather than
+    // looking forever for a nonce that gives r != 0
+    let signature = key.sign_with_nonce(b"hello", &BigUint::from(12345));
+    assert!(signature.r.is_zero());
+    assert!(key.sign(b"hello").is_err());
+
+    // and a genuine public key checked with g = 0 accepts a signature with r = 0 for anything, as
+    // long as the verifier doesn't check that r is in range
+    let public_key = PublicKey {
+        parameters: tamper(&parameters, BigUint::zero()),
+        y: PrivateKey::generate(&parameters).public_key().y,
+    };
+    let magic = magic_signature_zero();
+    for message in [&b"Hello, world"[..], b"Goodbye, world"].iter() {
+        assert!(public_key.verify_unchecked(message, &magic));
+        assert!(public_key.verify_unchecked(message, &signature));
+        assert!(!public_key.verify(message, &magic));
+    }
+}
+
+#[test]
+fn verify() {
+    let parameters = Parameters::cryptopals();
+    let key = PrivateKey::generate(&parameters);
+
+    // the verifier is talked into using g = p + 1 with a real public key
+    let p_plus_one = &parameters.p + &BigUint::one();
+    let public_key = PublicKey {
+        parameters: tamper(&parameters, p_plus_one),
+        y: key.public_key().y,
+    };
+
+    let magic = magic_signature_p_plus_one(&public_key);
+    for message in [&b"Hello, world"[..], b"Goodbye, world"].iter() {
+        assert!(public_key.verify(message, &magic));
+    }
+
+    // the honest parameters don't fall for it, and checking the tampered ones would have caught it
+    assert!(!key.public_key().verify(b"Hello, world", &magic));
+    assert!(!public_key.parameters.is_valid());
+}
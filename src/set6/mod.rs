@@ -0,0 +1,5 @@
+mod c41;
+mod c42;
+mod c43;
+mod c44;
+mod c45;
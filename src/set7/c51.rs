@@ -0,0 +1,162 @@
+use crate::block_ciphers::{Aes, Input, Mode};
+use flate2::{write::DeflateEncoder, Compression};
+use std::io::Write;
+
+// formats an HTTP request carrying a secret session cookie around an attacker-controlled body,
+// compresses it, encrypts it with a fresh IV or nonce every time, and reveals nothing but the
+// length of the result
+struct Oracle {
+    aes: Aes,
+    mode: Mode,
+    session_id: &'static str,
+}
+
+impl Oracle {
+    fn new(mode: Mode, session_id: &'static str) -> Self {
+        let mut key = [0u8; Aes::BLOCK_SIZE];
+        key.iter_mut().for_each(|byte| *byte = rand::random());
+
+        Oracle {
+            aes: Aes::new(key, mode),
+            mode,
+            session_id,
+        }
+    }
+
+    fn format_request(&self, body: &[u8]) -> Vec<u8> {
+        [
+            format!(
+                "POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid={}\nContent-Length: {}\n",
+                self.session_id,
+                body.len()
+            )
+            .as_bytes(),
+            body,
+        ]
+        .concat()
+    }
+
+    fn compress(plaintext: &[u8]) -> Vec<u8> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(plaintext).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn length<T>(&mut self, body: T) -> usize
+    where
+        T: AsRef<[u8]>,
+    {
+        let compressed = Self::compress(&self.format_request(body.as_ref()));
+
+        let input = match self.mode {
+            Mode::Cbc => {
+                let mut iv = [0u8; Aes::BLOCK_SIZE];
+                iv.iter_mut().for_each(|byte| *byte = rand::random());
+                Input::Iv(iv)
+            }
+            Mode::Ctr => Input::Nonce(rand::random()),
+            Mode::Ecb => Input::Nothing,
+        };
+
+        self.aes.encrypt(compressed, input).unwrap().len()
+    }
+}
+
+const KNOWN_PREFIX: &[u8] = b"\nCookie: sessionid=";
+const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=\n";
+
+// bytes that can't appear in the cookie, so that filler made of them never matches anything in
+// the request
+const FILLER: &[u8] = b"!@#$%^&*()-_[]{}<>|~`;:,.?'\"";
+
+// APPROACH
+//
+// DEFLATE replaces a string it has already seen with a short reference back to it. if the body
+// contains the tail of "\nCookie: sessionid=" plus the part of the cookie we know, followed by a
+// guess at the next character, a right guess is swallowed by the reference back to the real cookie
+// and a wrong one costs a literal. we keep the known window at a fixed 19 bytes so that the
+// reference always falls in the same match-length bucket and costs the same number of bits.
+//
+// lengths aren't exact, though. they are rounded to whole bytes, and a block cipher rounds them
+// further to whole blocks. so each round grows some filler that can't match anything until the
+// request spills over a boundary, then backs off by a byte, which leaves it just short of the
+// edge. there, each candidate is tried twice: right after the window, and after some junk that
+// breaks the match. a wrong candidate costs a literal either way and comes out the same length,
+// but the right one is often shorter, so it gets a vote. a candidate that pulls ahead of the rest
+// by a couple of votes is the next character, and the cookie is done when that is the newline
+// that ends the header. a newline that wins by a fluke would cut the cookie short, so a newline
+// goes to a second vote, whose winner is taken instead
+fn recover_session_id(oracle: &mut Oracle) -> Option<String> {
+    const MAX_LENGTH: usize = 128;
+
+    let mut session_id = Vec::new();
+
+    while session_id.len() < MAX_LENGTH {
+        let known = [KNOWN_PREFIX, &session_id].concat();
+        let window = &known[known.len() - KNOWN_PREFIX.len()..];
+
+        let byte = match next_byte(oracle, window)? {
+            b'\n' => next_byte(oracle, window)?,
+            byte => byte,
+        };
+
+        match byte {
+            b'\n' => return String::from_utf8(session_id).ok(),
+            byte => session_id.push(byte),
+        }
+    }
+
+    None
+}
+
+// the character that follows `window` in the request, by the vote described above
+fn next_byte(oracle: &mut Oracle, window: &[u8]) -> Option<u8> {
+    const MAX_ROUNDS: usize = 64;
+    const LEAD: usize = 2;
+    const JUNK: &[u8] = b"{}";
+
+    let mut filler = Vec::new();
+    let mut votes = vec![0; ALPHABET.len()];
+
+    for _ in 0..MAX_ROUNDS {
+        // grow the filler until the request spills over a boundary, then back off by a byte
+        let base = oracle.length([&filler[..], window, JUNK].concat());
+        loop {
+            filler.push(FILLER[rand::random::<usize>() % FILLER.len()]);
+            if oracle.length([&filler[..], window, JUNK].concat()) > base {
+                break;
+            }
+        }
+        let last = filler.pop()?;
+
+        for (candidate, vote) in ALPHABET.iter().zip(votes.iter_mut()) {
+            let with = oracle.length([&filler[..], window, &[*candidate], JUNK].concat());
+            let without = oracle.length([&filler[..], window, JUNK, &[*candidate]].concat());
+            if with < without {
+                *vote += 1;
+            }
+        }
+        filler.push(last);
+
+        let mut ranked = ALPHABET.iter().zip(&votes).collect::<Vec<_>>();
+        ranked.sort_by_key(|(_, vote)| std::cmp::Reverse(**vote));
+        if *ranked[0].1 >= *ranked[1].1 + LEAD {
+            return Some(*ranked[0].0);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn verify() {
+    let session_id = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+
+    // a stream cipher leaks the exact length of the compressed request
+    let mut oracle = Oracle::new(Mode::Ctr, session_id);
+    assert_eq!(recover_session_id(&mut oracle).unwrap(), session_id);
+
+    // a block cipher only leaks the number of blocks, which the filler makes up for
+    let mut oracle = Oracle::new(Mode::Cbc, session_id);
+    assert_eq!(recover_session_id(&mut oracle).unwrap(), session_id);
+}
@@ -0,0 +1,164 @@
+use crate::toy_hash::ToyHash;
+use std::collections::HashMap;
+
+pub type Block = [u8; ToyHash::BLOCK_SIZE];
+
+pub fn random_block() -> Block {
+    let mut block = [0u8; ToyHash::BLOCK_SIZE];
+    block.iter_mut().for_each(|byte| *byte = rand::random());
+    block
+}
+
+// a birthday search for two different blocks that take `state` to the same next state. this takes
+// about 2^(b/2) calls to the compression function for a b-bit state
+pub fn find_collision(hash: &ToyHash, state: u32) -> (Block, Block, u32) {
+    let mut seen: HashMap<u32, Block> = HashMap::new();
+
+    loop {
+        let block = random_block();
+        let next_state = hash.compress(state, &block);
+
+        match seen.get(&next_state) {
+            Some(other) if *other != block => return (*other, block, next_state),
+            _ => {
+                seen.insert(next_state, block);
+            }
+        }
+    }
+}
+
+// a chain of n colliding block pairs starting from `state`. picking either block of each pair
+// gives 2^n different messages of n blocks that all end up in the same state
+pub struct Multicollision {
+    pub pairs: Vec<(Block, Block)>,
+    pub state: u32,
+}
+
+impl Multicollision {
+    pub fn new(hash: &ToyHash, state: u32, n: usize) -> Self {
+        let mut multicollision = Self {
+            pairs: Vec::with_capacity(n),
+            state,
+        };
+        for _ in 0..n {
+            multicollision.extend(hash);
+        }
+
+        multicollision
+    }
+
+    // doubles the number of colliding messages with one more collision
+    pub fn extend(&mut self, hash: &ToyHash) {
+        let (block1, block2, state) = find_collision(hash, self.state);
+        self.pairs.push((block1, block2));
+        self.state = state;
+    }
+
+    // the message that takes block1 or block2 from each pair according to the bits of `index`
+    pub fn message(&self, index: u64) -> Vec<u8> {
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (block1, block2))| {
+                if (index >> i) & 1 == 0 {
+                    block1.to_vec()
+                } else {
+                    block2.to_vec()
+                }
+            })
+            .collect()
+    }
+
+    pub fn messages(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..1u64 << self.pairs.len()).map(move |index| self.message(index))
+    }
+}
+
+// runs `hash` over every message of the multicollision from `state`, sharing the work for common
+// prefixes, and looks for two messages that end up in the same state
+fn colliding_messages(
+    hash: &ToyHash,
+    state: u32,
+    multicollision: &Multicollision,
+) -> Option<(u64, u64)> {
+    let mut seen: HashMap<u32, u64> = HashMap::new();
+    let mut stack = vec![(state, 0, 0u64)];
+
+    while let Some((state, depth, index)) = stack.pop() {
+        if depth == multicollision.pairs.len() {
+            if let Some(other) = seen.insert(state, index) {
+                return Some((other, index));
+            }
+            continue;
+        }
+
+        let (block1, block2) = &multicollision.pairs[depth];
+        stack.push((hash.compress(state, block1), depth + 1, index));
+        stack.push((hash.compress(state, block2), depth + 1, index | 1 << depth));
+    }
+
+    None
+}
+
+// APPROACH
+//
+// h(m) = f(m) || g(m) looks like it should be as strong as f and g together: b1 + b2 bits, so
+// 2^((b1 + b2) / 2) work to find a collision. but a multicollision in f is cheap. each extra
+// collision doubles the number of messages that collide in f, for only 2^(b1/2) more work. with
+// 2^(b2/2) messages that all collide in f, there is a good chance that two of them also collide in
+// g (the birthday bound again), and if not we double the number of messages and try again. the
+// total is about (b2/2) * 2^(b1/2) + 2^(b2/2) calls, nowhere near 2^((b1 + b2) / 2).
+//
+// all of the messages are the same length, so if two of them reach the same state in both f and g
+// they also collide after the length padding
+pub fn cascade_collision(f: &ToyHash, g: &ToyHash) -> (Vec<u8>, Vec<u8>) {
+    let mut multicollision =
+        Multicollision::new(f, f.initial_state(), (g.state_bits() as usize).div_ceil(2));
+
+    loop {
+        if let Some((index1, index2)) = colliding_messages(g, g.initial_state(), &multicollision) {
+            return (
+                multicollision.message(index1),
+                multicollision.message(index2),
+            );
+        }
+
+        multicollision.extend(f);
+    }
+}
+
+#[test]
+fn test_multicollision() {
+    let f = ToyHash::new(16);
+    let multicollision = Multicollision::new(&f, f.initial_state(), 5);
+
+    let digests = multicollision
+        .messages()
+        .map(|message| f.digest(message))
+        .collect::<Vec<u32>>();
+    assert_eq!(digests.len(), 32);
+    assert!(digests.iter().all(|digest| *digest == digests[0]));
+
+    let mut messages = multicollision.messages().collect::<Vec<Vec<u8>>>();
+    messages.sort();
+    messages.dedup();
+    assert_eq!(messages.len(), 32);
+}
+
+#[test]
+fn verify() {
+    let f = ToyHash::new(16);
+    let g = ToyHash::new(32);
+    let h = |message: &[u8]| (f.digest(message), g.digest(message));
+
+    let (message1, message2) = cascade_collision(&f, &g);
+    let (f_calls, g_calls) = (f.calls(), g.calls());
+
+    assert_ne!(message1, message2);
+    assert_eq!(h(&message1), h(&message2));
+
+    // 16 collisions in f at about 2^8 calls each, then the birthday search in g over 2^16 or so
+    // messages. far fewer than the 2^24 a birthday attack on the 48-bit cascade would need
+    assert!(f_calls < 1 << 14);
+    assert!(g_calls < 1 << 21);
+}
@@ -0,0 +1,170 @@
+use super::c52::{self, Block};
+use crate::toy_hash::ToyHash;
+use std::collections::HashMap;
+
+// finds a single block and a message of `dummy_blocks` + 1 blocks that both take `state` to the
+// same next state. the long message is the dummy blocks followed by one chosen block, so the
+// birthday search runs from two different starting states at once
+fn find_collision_of_lengths(
+    hash: &ToyHash,
+    state: u32,
+    dummy_blocks: usize,
+) -> (Block, Vec<u8>, u32) {
+    let dummy = vec![0u8; dummy_blocks * ToyHash::BLOCK_SIZE];
+    let dummy_state = hash.iterate(state, &dummy);
+
+    let mut short: HashMap<u32, Block> = HashMap::new();
+    let mut long: HashMap<u32, Block> = HashMap::new();
+
+    loop {
+        let block = c52::random_block();
+        let next_state = hash.compress(state, &block);
+        if let Some(last) = long.get(&next_state) {
+            return (block, [&dummy[..], last].concat(), next_state);
+        }
+        short.insert(next_state, block);
+
+        let last = c52::random_block();
+        let next_state = hash.compress(dummy_state, &last);
+        if let Some(block) = short.get(&next_state) {
+            return (*block, [&dummy[..], &last].concat(), next_state);
+        }
+        long.insert(next_state, last);
+    }
+}
+
+// a (k, k + 2^k - 1) expandable message: k pieces, each a choice between one block and
+// 2^(k - 1 - i) + 1 blocks with the same result, so that it can be made any length from k to
+// k + 2^k - 1 blocks and still end in the same state
+pub struct ExpandableMessage {
+    pieces: Vec<(Block, Vec<u8>)>,
+    pub state: u32,
+}
+
+impl ExpandableMessage {
+    pub fn new(hash: &ToyHash, state: u32, k: usize) -> Self {
+        let mut pieces = Vec::with_capacity(k);
+        let mut state = state;
+
+        for i in 0..k {
+            let (short, long, next_state) =
+                find_collision_of_lengths(hash, state, 1 << (k - 1 - i));
+            pieces.push((short, long));
+            state = next_state;
+        }
+
+        Self { pieces, state }
+    }
+
+    pub fn min_blocks(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn max_blocks(&self) -> usize {
+        self.pieces.len() + (1 << self.pieces.len()) - 1
+    }
+
+    // the message of exactly `blocks` blocks. the blocks beyond the minimum are written in binary,
+    // and piece i is long when bit k - 1 - i is set
+    pub fn produce(&self, blocks: usize) -> Vec<u8> {
+        assert!((self.min_blocks()..=self.max_blocks()).contains(&blocks));
+
+        let k = self.pieces.len();
+        let extra = blocks - k;
+
+        self.pieces
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (short, long))| {
+                if (extra >> (k - 1 - i)) & 1 == 1 {
+                    long.clone()
+                } else {
+                    short.to_vec()
+                }
+            })
+            .collect()
+    }
+}
+
+// APPROACH
+//
+// a generic second preimage for a b-bit hash should take 2^b work, but a long target message gives
+// us a lot of targets: the intermediate state after each of its 2^k blocks. a random block from
+// the right starting point hits one of them after about 2^(b - k) tries. the catch is the length
+// padding, which makes a shortcut into the middle of the message hash differently unless the
+// forgery is exactly as long as the target.
+//
+// an expandable message fixes that. it gets from the initial state to one fixed state in any
+// number of blocks between k and k + 2^k - 1, and costs only about k * 2^(b/2 + 1) + 2^k to
+// build. from its final state we search for a bridge block that lands on the target's state after
+// block j + 1 (for some j in range), then pad the expandable message out to j blocks, add the
+// bridge, and copy the rest of the target. the result has the target's length, goes through the
+// same state after block j + 1, and so has the same hash
+pub fn second_preimage(hash: &ToyHash, target: &[u8], k: usize) -> Option<Vec<u8>> {
+    // the state after each whole block of the target, indexed by the number of blocks it follows.
+    // a bridge block can take the place of block j for j from k to k + 2^k - 1
+    let mut states: HashMap<u32, usize> = HashMap::new();
+    let mut state = hash.initial_state();
+    for (j, block) in target.chunks_exact(ToyHash::BLOCK_SIZE).enumerate() {
+        state = hash.compress(state, block);
+
+        if j >= k && j < k + (1 << k) {
+            states.insert(state, j);
+        }
+    }
+
+    if states.is_empty() {
+        return None;
+    }
+
+    let expandable = ExpandableMessage::new(hash, hash.initial_state(), k);
+
+    let (bridge, j) = loop {
+        let bridge = c52::random_block();
+        if let Some(j) = states.get(&hash.compress(expandable.state, &bridge)) {
+            break (bridge, *j);
+        }
+    };
+
+    let forgery = [
+        &expandable.produce(j)[..],
+        &bridge,
+        &target[(j + 1) * ToyHash::BLOCK_SIZE..],
+    ]
+    .concat();
+
+    Some(forgery)
+}
+
+#[test]
+fn test_expandable_message() {
+    let hash = ToyHash::new(16);
+    let expandable = ExpandableMessage::new(&hash, hash.initial_state(), 5);
+
+    for blocks in expandable.min_blocks()..=expandable.max_blocks() {
+        let message = expandable.produce(blocks);
+
+        assert_eq!(message.len(), blocks * ToyHash::BLOCK_SIZE);
+        assert_eq!(
+            hash.iterate(hash.initial_state(), &message),
+            expandable.state
+        );
+    }
+}
+
+#[test]
+fn verify() {
+    let hash = ToyHash::new(24);
+    let k = 16;
+
+    // 2^16 blocks, with a partial block at the end for good measure
+    let target = (0..(1 << k) * ToyHash::BLOCK_SIZE + 5)
+        .map(|_| rand::random::<u8>())
+        .collect::<Vec<u8>>();
+
+    let forgery = second_preimage(&hash, &target, k).unwrap();
+
+    assert_ne!(forgery, target);
+    assert_eq!(forgery.len(), target.len());
+    assert_eq!(hash.digest(&forgery), hash.digest(&target));
+}
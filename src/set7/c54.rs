@@ -0,0 +1,193 @@
+use super::c52::{self, Block};
+use crate::toy_hash::{self, ToyHash};
+use std::collections::{HashMap, HashSet};
+use std::thread;
+
+// finds a block for each of two states such that both end up in the same next state, by running
+// the birthday search from both at once
+fn find_collision_between(hash: &ToyHash, state1: u32, state2: u32) -> (Block, Block, u32) {
+    let mut from1: HashMap<u32, Block> = HashMap::new();
+    let mut from2: HashMap<u32, Block> = HashMap::new();
+
+    loop {
+        let block1 = c52::random_block();
+        let next_state = hash.compress(state1, &block1);
+        if let Some(block2) = from2.get(&next_state) {
+            return (block1, *block2, next_state);
+        }
+        from1.insert(next_state, block1);
+
+        let block2 = c52::random_block();
+        let next_state = hash.compress(state2, &block2);
+        if let Some(block1) = from1.get(&next_state) {
+            return (*block1, block2, next_state);
+        }
+        from2.insert(next_state, block2);
+    }
+}
+
+// a diamond structure (collision tree) of depth k. it starts from 2^k leaf states and pairs them
+// up level by level, with one block per node that takes it to the same state as its sibling,
+// until everything comes together at the root. every leaf has a path of k blocks to the root
+pub struct DiamondStructure {
+    leaves: HashMap<u32, usize>,
+    blocks: Vec<Vec<Block>>,
+    pub root: u32,
+}
+
+impl DiamondStructure {
+    // the collision searches for the pairs at each level are independent of each other, so they
+    // are shared out between all of the cores
+    pub fn new(hash: &ToyHash, k: usize) -> Self {
+        let mut leaves = HashSet::new();
+        while leaves.len() < 1 << k {
+            leaves.insert(rand::random::<u32>() >> (32 - hash.state_bits()));
+        }
+
+        let leaves: Vec<u32> = leaves.into_iter().collect();
+        let threads = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+
+        let mut blocks = Vec::with_capacity(k);
+        let mut states = leaves.clone();
+
+        while states.len() > 1 {
+            let pairs: Vec<&[u32]> = states.chunks(2).collect();
+            let chunk_size = pairs.len().div_ceil(threads);
+
+            let collisions: Vec<(Block, Block, u32)> = thread::scope(|scope| {
+                let handles: Vec<_> = pairs
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter()
+                                .map(|pair| find_collision_between(hash, pair[0], pair[1]))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().unwrap())
+                    .collect()
+            });
+
+            blocks.push(
+                collisions
+                    .iter()
+                    .flat_map(|(block1, block2, _)| vec![*block1, *block2])
+                    .collect(),
+            );
+            states = collisions.iter().map(|(_, _, state)| *state).collect();
+        }
+
+        Self {
+            leaves: leaves
+                .into_iter()
+                .enumerate()
+                .map(|(i, s)| (s, i))
+                .collect(),
+            blocks,
+            root: states[0],
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.blocks.len()
+    }
+
+    // the blocks that take `leaf` to the root, if it is one of the leaves
+    pub fn path(&self, leaf: u32) -> Option<Vec<u8>> {
+        let mut index = *self.leaves.get(&leaf)?;
+        let mut path = Vec::with_capacity(self.depth() * ToyHash::BLOCK_SIZE);
+
+        for level in &self.blocks {
+            path.extend_from_slice(&level[index]);
+            index /= 2;
+        }
+
+        Some(path)
+    }
+}
+
+// a hash committed to before the message it is a hash of is known. the message will be
+// `prefix_blocks` blocks of whatever we like, one linking block, and a path through the diamond
+// structure, so its length (and so its padding) is known in advance
+pub struct Prediction {
+    diamond: DiamondStructure,
+    prefix_blocks: usize,
+    pub digest: u32,
+}
+
+impl Prediction {
+    pub fn new(hash: &ToyHash, k: usize, prefix_blocks: usize) -> Self {
+        let diamond = DiamondStructure::new(hash, k);
+        let message_length = (prefix_blocks + 1 + k) * ToyHash::BLOCK_SIZE;
+        let digest = hash.iterate(diamond.root, toy_hash::padding(message_length as u64));
+
+        Self {
+            diamond,
+            prefix_blocks,
+            digest,
+        }
+    }
+
+    // APPROACH
+    //
+    // a hash prediction is a preimage attack in disguise, except that we get to do most of the
+    // work before we know the message. the diamond structure gives 2^k states that all lead to the
+    // root, and so to the prediction. once the real content is known, we hash it and search for a
+    // single block that takes it to any one of the leaves, which takes about 2^(b - k) tries
+    // instead of 2^b. the path from that leaf finishes the message
+    pub fn herd(&self, hash: &ToyHash, content: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let prefix_length = self.prefix_blocks * ToyHash::BLOCK_SIZE;
+        if content.len() > prefix_length {
+            return Err("the content is longer than the prediction allows for");
+        }
+
+        // fill out the rest of the prefix with spaces
+        let mut prefix = content.to_vec();
+        prefix.resize(prefix_length, b' ');
+        let state = hash.iterate(hash.initial_state(), &prefix);
+
+        loop {
+            let link = c52::random_block();
+            if let Some(path) = self.diamond.path(hash.compress(state, &link)) {
+                return Ok([&prefix[..], &link, &path].concat());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_diamond_structure() {
+    let hash = ToyHash::new(16);
+    let diamond = DiamondStructure::new(&hash, 4);
+
+    assert_eq!(diamond.depth(), 4);
+    assert_eq!(diamond.leaves.len(), 16);
+
+    for leaf in diamond.leaves.keys() {
+        let path = diamond.path(*leaf).unwrap();
+        assert_eq!(hash.iterate(*leaf, &path), diamond.root);
+    }
+}
+
+#[test]
+fn verify() {
+    let hash = ToyHash::new(20);
+    let prediction = Prediction::new(&hash, 8, 4);
+
+    // the prediction is published before the season ends...
+    let digest = prediction.digest;
+
+    // ...and the message to match it is only written after
+    let scores = b"ALCS: Red Sox 4, Yankees 3. NLCS: Cubs 5, Dodgers 2.";
+    let message = prediction.herd(&hash, scores).unwrap();
+
+    assert!(message.starts_with(scores));
+    assert_eq!(hash.digest(&message), digest);
+}
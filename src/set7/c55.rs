@@ -0,0 +1,402 @@
+use crate::md4::Md4;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+// a condition on one bit of an intermediate MD4 state word: the bit is cleared, set, or equal to
+// the same bit of the word `back` steps earlier
+#[derive(Clone, Copy)]
+enum Condition {
+    Zero(u32),
+    One(u32),
+    Equal(u32, usize),
+}
+
+use Condition::{Equal, One, Zero};
+
+impl Condition {
+    fn bit(&self) -> u32 {
+        match self {
+            Zero(bit) | One(bit) | Equal(bit, _) => *bit,
+        }
+    }
+}
+
+// the sufficient conditions from table 6 of Wang et al., "Cryptanalysis of the Hash Functions MD4
+// and RIPEMD", for the 16 steps of round 1 (a1, d1, c1, b1, a2, ...). bits are numbered from 0
+// here, where the paper numbers them from 1
+const ROUND_1_CONDITIONS: [&[Condition]; 16] = [
+    &[Equal(6, 1)],
+    &[Zero(6), Equal(7, 1), Equal(10, 1)],
+    &[One(6), One(7), Zero(10), Equal(25, 1)],
+    &[One(6), Zero(7), Zero(10), Zero(25)],
+    &[One(7), One(10), Zero(25), Equal(13, 1)],
+    &[
+        Zero(13),
+        Equal(18, 1),
+        Equal(19, 1),
+        Equal(20, 1),
+        Equal(21, 1),
+        One(25),
+    ],
+    &[
+        Equal(12, 1),
+        Zero(13),
+        Equal(14, 1),
+        Zero(18),
+        Zero(19),
+        One(20),
+        Zero(21),
+    ],
+    &[
+        One(12),
+        One(13),
+        Zero(14),
+        Equal(16, 1),
+        Zero(18),
+        Zero(19),
+        Zero(20),
+        Zero(21),
+    ],
+    &[
+        One(12),
+        One(13),
+        One(14),
+        Zero(16),
+        Zero(18),
+        Zero(19),
+        Zero(20),
+        One(21),
+        Equal(22, 1),
+        Equal(25, 1),
+    ],
+    &[
+        One(12),
+        One(13),
+        One(14),
+        Zero(16),
+        Zero(19),
+        One(20),
+        One(21),
+        Zero(22),
+        One(25),
+        Equal(29, 1),
+    ],
+    &[
+        One(16),
+        Zero(19),
+        Zero(20),
+        Zero(21),
+        Zero(22),
+        Zero(25),
+        One(29),
+        Equal(31, 1),
+    ],
+    &[
+        Zero(19),
+        One(20),
+        One(21),
+        Equal(22, 1),
+        One(25),
+        Zero(29),
+        Zero(31),
+    ],
+    &[
+        Zero(22),
+        Zero(25),
+        Equal(26, 1),
+        Equal(28, 1),
+        One(29),
+        Zero(31),
+    ],
+    &[Zero(22), Zero(25), One(26), One(28), Zero(29), One(31)],
+    &[Equal(18, 1), One(22), One(25), Zero(26), Zero(28), Zero(29)],
+    &[Zero(18), One(25), One(26), One(28), Zero(29)],
+];
+
+// the conditions on the first three steps of round 2 (a5, d5 and c5)
+const ROUND_2_CONDITIONS: [&[Condition]; 3] = [
+    &[Equal(18, 2), One(25), Zero(26), One(28), One(31)],
+    &[
+        Equal(18, 1),
+        Equal(25, 2),
+        Equal(26, 2),
+        Equal(28, 2),
+        Equal(31, 2),
+    ],
+    &[
+        Equal(25, 1),
+        Equal(26, 1),
+        Equal(28, 1),
+        Equal(29, 1),
+        Equal(31, 1),
+    ],
+];
+
+const SHIFTS: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+fn f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+fn g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+fn h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+// the word of the message block that step i reads, and the value the step adds in
+fn step_input(i: usize, words: &[u32; 16], s: &[u32]) -> (u32, u32) {
+    let (b, c, d) = (s[i + 3], s[i + 2], s[i + 1]);
+
+    match i / 16 {
+        0 => (words[i], f(b, c, d)),
+        1 => (
+            words[(i % 4) * 4 + (i % 16) / 4],
+            g(b, c, d).wrapping_add(0x5A827999),
+        ),
+        _ => (
+            words[[0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15][i % 16]],
+            h(b, c, d).wrapping_add(0x6ED9EBA1),
+        ),
+    }
+}
+
+// the intermediate state words of one compression, starting with the initial registers in the
+// order a, d, c, b. step i stores the word it computes at i + 4, so the registers it reads are
+// always the four words before that
+type States = [u32; 52];
+
+fn step(s: &mut States, words: &[u32; 16], i: usize) {
+    let (word, function) = step_input(i, words, s);
+    s[i + 4] = s[i]
+        .wrapping_add(function)
+        .wrapping_add(word)
+        .rotate_left(SHIFTS[i / 16][i % 4]);
+}
+
+fn initial_states() -> States {
+    let [a, b, c, d] = Md4::INITIAL_STATE;
+    let mut s = [0u32; 52];
+    s[..4].copy_from_slice(&[a, d, c, b]);
+    s
+}
+
+// the MD4 compression function applied to a single block from the initial registers
+fn compress(words: &[u32; 16]) -> [u32; 4] {
+    let mut s = initial_states();
+    for i in 0..48 {
+        step(&mut s, words, i);
+    }
+
+    let [a, b, c, d] = Md4::INITIAL_STATE;
+    [
+        a.wrapping_add(s[48]),
+        b.wrapping_add(s[51]),
+        c.wrapping_add(s[50]),
+        d.wrapping_add(s[49]),
+    ]
+}
+
+fn holds(condition: &Condition, s: &[u32], i: usize) -> bool {
+    let bit = condition.bit();
+
+    match condition {
+        Zero(_) => (s[i] >> bit) & 1 == 0,
+        One(_) => (s[i] >> bit) & 1 == 1,
+        Equal(_, back) => (s[i] >> bit) & 1 == (s[i - back] >> bit) & 1,
+    }
+}
+
+fn round_1_holds(s: &[u32]) -> bool {
+    ROUND_1_CONDITIONS
+        .iter()
+        .enumerate()
+        .all(|(i, conditions)| conditions.iter().all(|c| holds(c, s, i + 4)))
+}
+
+// works out the message word that makes step i (in round 1, where step i reads word i) produce
+// the state word already stored for it
+fn solve_word(s: &[u32], i: usize) -> u32 {
+    s[i + 4]
+        .rotate_right(SHIFTS[0][i % 4])
+        .wrapping_sub(s[i])
+        .wrapping_sub(f(s[i + 3], s[i + 2], s[i + 1]))
+}
+
+// rewrites the message so that every round 1 condition holds, returning the states it produces
+fn single_step_modifications(words: &mut [u32; 16]) -> States {
+    let mut s = initial_states();
+
+    for i in 0..16 {
+        step(&mut s, words, i);
+        let mut t = s[i + 4];
+
+        for condition in ROUND_1_CONDITIONS[i] {
+            let bit = condition.bit();
+            let value = match condition {
+                Zero(_) => 0,
+                One(_) => 1,
+                Equal(_, back) => (s[i + 4 - back] >> bit) & 1,
+            };
+            t = (t & !(1 << bit)) | (value << bit);
+        }
+
+        s[i + 4] = t;
+        words[i] = solve_word(&s, i);
+    }
+
+    s
+}
+
+// the message difference of the differential path: M' = M + (0, 2^31, 2^31 - 2^28, 0, ..., -2^16,
+// 0, 0, 0)
+fn partner(words: &[u32; 16]) -> [u32; 16] {
+    let mut partner = *words;
+    partner[1] = partner[1].wrapping_add(1 << 31);
+    partner[2] = partner[2].wrapping_add((1 << 31) - (1 << 28));
+    partner[12] = partner[12].wrapping_sub(1 << 16);
+    partner
+}
+
+// APPROACH
+//
+// Wang's attack picks a message difference, and a list of conditions on the intermediate states
+// of the first message, such that if every condition holds the difference cancels out by the end
+// of the compression function. the job is to find a message that satisfies as many of the
+// conditions as possible for free, and to brute force the rest.
+//
+// round 1 reads the message words in order, one per step, so each state word can be set to
+// anything we like by choosing the word that goes into its step (single-step modification): we
+// compute the state word, force its condition bits, and solve for the message word that gives it.
+//
+// round 2 reads the words again, so its state words are harder to control. a5 depends on m0, which
+// also produced a1. flipping bit i of a1 changes m0 by +-2^(i-3), which flips bit i of a5 as well.
+// the message words for the next four steps are then recomputed so that d1, c1, b1 and a2 (and so
+// everything after) stay the same (multi-step modification). the same trick fixes d5 through a2,
+// and c5 through a3 wherever that doesn't break a3's own conditions.
+//
+// the remaining conditions hold by chance, so we keep trying random messages until one gives a
+// collision
+fn attempt<R: rand::Rng>(rng: &mut R) -> Option<([u32; 16], [u32; 16])> {
+    let mut words = [0u32; 16];
+    rng.fill(&mut words[..]);
+
+    let mut s = single_step_modifications(&mut words);
+
+    // multi-step modifications for a5, d5 and c5, through a1, a2 and a3
+    for (j, conditions) in ROUND_2_CONDITIONS.iter().enumerate() {
+        let (target, shift) = (4 + 4 * j, [0, 2, 6][j]);
+
+        for condition in conditions.iter() {
+            for i in 16..17 + j {
+                step(&mut s, &words, i);
+            }
+            if holds(condition, &s, 20 + j) {
+                continue;
+            }
+
+            let (saved_words, saved_state) = (words, s[target]);
+            s[target] ^= 1 << ((condition.bit() + 32 - shift) % 32);
+            for (i, word) in words.iter_mut().enumerate().skip(target - 4).take(5) {
+                *word = solve_word(&s, i);
+            }
+
+            for i in 16..17 + j {
+                step(&mut s, &words, i);
+            }
+            if !round_1_holds(&s) || !holds(condition, &s, 20 + j) {
+                words = saved_words;
+                s[target] = saved_state;
+            }
+        }
+    }
+
+    let collision = partner(&words);
+    if compress(&words) == compress(&collision) && words != collision {
+        Some((words, collision))
+    } else {
+        None
+    }
+}
+
+fn to_bytes(words: &[u32; 16]) -> [u8; Md4::BLOCK_SIZE] {
+    let mut bytes = [0u8; Md4::BLOCK_SIZE];
+    for (chunk, word) in bytes.chunks_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+
+    bytes
+}
+
+// searches on every core for two different blocks with the same MD4 hash, returning them along
+// with the number of messages that were tried
+pub fn find_collision() -> ([u8; Md4::BLOCK_SIZE], [u8; Md4::BLOCK_SIZE], u64) {
+    let threads = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let found = AtomicBool::new(false);
+
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut rng = rand::thread_rng();
+                    let mut attempts = 0;
+
+                    while !found.load(Ordering::Relaxed) {
+                        attempts += 1;
+
+                        if let Some(collision) = attempt(&mut rng) {
+                            found.store(true, Ordering::Relaxed);
+                            return (Some(collision), attempts);
+                        }
+                    }
+
+                    (None, attempts)
+                })
+            })
+            .collect();
+
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let attempts = results.iter().map(|(_, attempts)| attempts).sum();
+    let (words1, words2) = results
+        .into_iter()
+        .find_map(|(collision, _)| collision)
+        .unwrap();
+
+    (to_bytes(&words1), to_bytes(&words2), attempts)
+}
+
+#[test]
+fn test_single_step_modifications() {
+    // every round 1 condition holds whatever message we start from
+    let mut rng = rand::thread_rng();
+
+    for _ in 0..100 {
+        let mut words = [0u32; 16];
+        rand::Rng::fill(&mut rng, &mut words[..]);
+        single_step_modifications(&mut words);
+
+        let mut s = initial_states();
+        for i in 0..16 {
+            step(&mut s, &words, i);
+        }
+        assert!(round_1_holds(&s));
+    }
+}
+
+#[test]
+fn verify() {
+    let (block1, block2, attempts) = find_collision();
+
+    assert_ne!(block1, block2);
+    assert_eq!(Md4::digest(block1), Md4::digest(block2));
+
+    // the modifications leave about one message in 2^16 to chance, where a birthday search would
+    // need 2^64
+    assert!((1..=1 << 24).contains(&attempts));
+}
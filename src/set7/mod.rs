@@ -0,0 +1,5 @@
+mod c51;
+mod c52;
+mod c53;
+mod c54;
+mod c55;
@@ -0,0 +1,189 @@
+use crate::hash::{Hash, LengthExtendable};
+use std::convert::TryFrom;
+
+// SHA-1 as specified in FIPS 180-4. the internal state can be set directly, which is all a
+// length-extension attack needs
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Sha1 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const OUTPUT_SIZE: usize = 20;
+    const INITIAL_STATE: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    // resumes hashing from the given registers, as if `length` bytes (a multiple of the block
+    // size) had already been processed
+    pub fn from_state(state: [u32; 5], length: u64) -> Self {
+        assert!(
+            length.is_multiple_of(Self::BLOCK_SIZE as u64),
+            "the processed length must be a whole number of blocks"
+        );
+
+        Self {
+            state,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length,
+        }
+    }
+
+    // resumes hashing from a digest, which is just the registers after the padded message
+    pub fn from_digest(digest: &[u8; Self::OUTPUT_SIZE], length: u64) -> Self {
+        let mut state = [0u32; 5];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        Self::from_state(state, length)
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha1 = Self::new();
+        sha1.update(message);
+        sha1.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        for byte in message.as_ref() {
+            self.buffer.push(*byte);
+
+            if self.buffer.len() == Self::BLOCK_SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.compress(&block);
+                self.length += Self::BLOCK_SIZE as u64;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::OUTPUT_SIZE] {
+        let message_length = self.length + self.buffer.len() as u64;
+        self.update(padding(message_length));
+
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        for (chunk, word) in digest.chunks_mut(4).zip(&self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 80];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = self.state;
+
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (register, value) in self.state.iter_mut().zip(&[a, b, c, d, e]) {
+            *register = register.wrapping_add(*value);
+        }
+    }
+}
+
+impl Hash for Sha1 {
+    const BLOCK_SIZE: usize = Sha1::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha1::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha1::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha1::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha1::finalize(self).to_vec()
+    }
+}
+
+impl LengthExtendable for Sha1 {
+    fn padding(message_length: u64) -> Vec<u8> {
+        padding(message_length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Option<Self> {
+        let digest = <&[u8; Sha1::OUTPUT_SIZE]>::try_from(digest).ok()?;
+        Some(Sha1::from_digest(digest, length))
+    }
+}
+
+// the Merkle-Damgard padding appended to a message of `message_length` bytes: a 1 bit, zeroes up
+// to 8 bytes short of a block boundary, then the message length in bits as a big-endian u64
+pub fn padding(message_length: u64) -> Vec<u8> {
+    let zeroes = (Sha1::BLOCK_SIZE * 2 - 9 - (message_length as usize % Sha1::BLOCK_SIZE))
+        % Sha1::BLOCK_SIZE;
+
+    [
+        &[0x80][..],
+        &vec![0u8; zeroes],
+        &(message_length * 8).to_be_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_sha1() {
+    use crate::helpers::Hex;
+
+    let hash = |message: &[u8]| Hex::from_bytes(&Sha1::digest(message)).to_string();
+
+    assert_eq!(hash(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    assert_eq!(hash(b"abc"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+    assert_eq!(
+        hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+    );
+    assert_eq!(
+        hash(&[b'a'; 1_000_000]),
+        "34aa973cd4c4daa4f61eeb2bdbad27316534016f"
+    );
+
+    // streaming in uneven pieces gives the same digest
+    let mut sha1 = Sha1::new();
+    for chunk in b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".chunks(7) {
+        sha1.update(chunk);
+    }
+    assert_eq!(
+        Hex::from_bytes(&sha1.finalize()).to_string(),
+        "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+    );
+}
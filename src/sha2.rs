@@ -0,0 +1,678 @@
+use crate::hash::{Hash, LengthExtendable};
+use std::convert::TryFrom;
+
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+// SHA-256 as specified in FIPS 180-4. like SHA-1, the internal state can be set directly
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: Vec<u8>,
+    length: u64,
+}
+
+impl Sha256 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const OUTPUT_SIZE: usize = 32;
+    const INITIAL_STATE: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    // resumes hashing from the given registers, as if `length` bytes (a multiple of the block
+    // size) had already been processed
+    pub fn from_state(state: [u32; 8], length: u64) -> Self {
+        assert!(
+            length.is_multiple_of(Self::BLOCK_SIZE as u64),
+            "the processed length must be a whole number of blocks"
+        );
+
+        Self {
+            state,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length,
+        }
+    }
+
+    // resumes hashing from a digest, which is just the registers after the padded message
+    pub fn from_digest(digest: &[u8; Self::OUTPUT_SIZE], length: u64) -> Self {
+        let mut state = [0u32; 8];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        Self::from_state(state, length)
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha256 = Self::new();
+        sha256.update(message);
+        sha256.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        for byte in message.as_ref() {
+            self.buffer.push(*byte);
+
+            if self.buffer.len() == Self::BLOCK_SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.compress(&block);
+                self.length += Self::BLOCK_SIZE as u64;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::OUTPUT_SIZE] {
+        let message_length = self.length + self.buffer.len() as u64;
+        self.update(padding(message_length));
+
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        for (chunk, word) in digest.chunks_mut(4).zip(&self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+
+        for (k, word) in K256.iter().zip(&w) {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(*k)
+                .wrapping_add(*word);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (register, value) in self.state.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+            *register = register.wrapping_add(*value);
+        }
+    }
+}
+
+impl Hash for Sha256 {
+    const BLOCK_SIZE: usize = Sha256::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha256::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha256::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha256::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha256::finalize(self).to_vec()
+    }
+}
+
+// the truncated variants (SHA-224, SHA-384, SHA-512/256) leave part of the state out of the
+// digest, so only the full-width hashes can be resumed
+impl LengthExtendable for Sha256 {
+    fn padding(message_length: u64) -> Vec<u8> {
+        padding(message_length)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Option<Self> {
+        let digest = <&[u8; Sha256::OUTPUT_SIZE]>::try_from(digest).ok()?;
+        Some(Sha256::from_digest(digest, length))
+    }
+}
+
+// SHA-224 is SHA-256 with different initial registers and the output cut down to 28 bytes
+#[derive(Clone)]
+pub struct Sha224(Sha256);
+
+impl Sha224 {
+    pub const BLOCK_SIZE: usize = 64;
+    pub const OUTPUT_SIZE: usize = 28;
+    const INITIAL_STATE: [u32; 8] = [
+        0xc1059ed8, 0x367cd507, 0x3070dd17, 0xf70e5939, 0xffc00b31, 0x68581511, 0x64f98fa7,
+        0xbefa4fa4,
+    ];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    pub fn from_state(state: [u32; 8], length: u64) -> Self {
+        Self(Sha256::from_state(state, length))
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha224 = Self::new();
+        sha224.update(message);
+        sha224.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.0.update(message)
+    }
+
+    pub fn finalize(self) -> [u8; Self::OUTPUT_SIZE] {
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        digest.copy_from_slice(&self.0.finalize()[..Self::OUTPUT_SIZE]);
+        digest
+    }
+}
+
+impl Hash for Sha224 {
+    const BLOCK_SIZE: usize = Sha224::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha224::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha224::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha224::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha224::finalize(self).to_vec()
+    }
+}
+
+// SHA-512 as specified in FIPS 180-4. it has the same structure as SHA-256, with 64-bit words,
+// 128-byte blocks, 80 rounds and a 128-bit length
+#[derive(Clone)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: Vec<u8>,
+    length: u128,
+}
+
+impl Sha512 {
+    pub const BLOCK_SIZE: usize = 128;
+    pub const OUTPUT_SIZE: usize = 64;
+    const INITIAL_STATE: [u64; 8] = [
+        0x6a09e667f3bcc908,
+        0xbb67ae8584caa73b,
+        0x3c6ef372fe94f82b,
+        0xa54ff53a5f1d36f1,
+        0x510e527fade682d1,
+        0x9b05688c2b3e6c1f,
+        0x1f83d9abfb41bd6b,
+        0x5be0cd19137e2179,
+    ];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    // resumes hashing from the given registers, as if `length` bytes (a multiple of the block
+    // size) had already been processed
+    pub fn from_state(state: [u64; 8], length: u128) -> Self {
+        assert!(
+            length.is_multiple_of(Self::BLOCK_SIZE as u128),
+            "the processed length must be a whole number of blocks"
+        );
+
+        Self {
+            state,
+            buffer: Vec::with_capacity(Self::BLOCK_SIZE),
+            length,
+        }
+    }
+
+    // resumes hashing from a digest, which is just the registers after the padded message
+    pub fn from_digest(digest: &[u8; Self::OUTPUT_SIZE], length: u128) -> Self {
+        let mut state = [0u64; 8];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(8)) {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            *word = u64::from_be_bytes(bytes);
+        }
+
+        Self::from_state(state, length)
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha512 = Self::new();
+        sha512.update(message);
+        sha512.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        for byte in message.as_ref() {
+            self.buffer.push(*byte);
+
+            if self.buffer.len() == Self::BLOCK_SIZE {
+                let block = std::mem::take(&mut self.buffer);
+                self.compress(&block);
+                self.length += Self::BLOCK_SIZE as u128;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; Self::OUTPUT_SIZE] {
+        let message_length = self.length + self.buffer.len() as u128;
+        self.update(padding_512(message_length));
+
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        for (chunk, word) in digest.chunks_mut(8).zip(&self.state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8]) {
+        let mut w = [0u64; 80];
+        for (i, chunk) in block.chunks(8).enumerate() {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(chunk);
+            w[i] = u64::from_be_bytes(bytes);
+        }
+        for i in 16..80 {
+            let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+            let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+
+        for (k, word) in K512.iter().zip(&w) {
+            let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+            let choice = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(choice)
+                .wrapping_add(*k)
+                .wrapping_add(*word);
+            let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+            let majority = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(majority);
+
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        for (register, value) in self.state.iter_mut().zip(&[a, b, c, d, e, f, g, h]) {
+            *register = register.wrapping_add(*value);
+        }
+    }
+}
+
+impl Hash for Sha512 {
+    const BLOCK_SIZE: usize = Sha512::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha512::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha512::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha512::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha512::finalize(self).to_vec()
+    }
+}
+
+impl LengthExtendable for Sha512 {
+    fn padding(message_length: u64) -> Vec<u8> {
+        padding_512(message_length as u128)
+    }
+
+    fn from_digest(digest: &[u8], length: u64) -> Option<Self> {
+        let digest = <&[u8; Sha512::OUTPUT_SIZE]>::try_from(digest).ok()?;
+        Some(Sha512::from_digest(digest, length as u128))
+    }
+}
+
+// SHA-384 is SHA-512 with different initial registers and the output cut down to 48 bytes. the
+// 16 bytes of state that are cut off are what protect it from length extension
+#[derive(Clone)]
+pub struct Sha384(Sha512);
+
+impl Sha384 {
+    pub const BLOCK_SIZE: usize = 128;
+    pub const OUTPUT_SIZE: usize = 48;
+    const INITIAL_STATE: [u64; 8] = [
+        0xcbbb9d5dc1059ed8,
+        0x629a292a367cd507,
+        0x9159015a3070dd17,
+        0x152fecd8f70e5939,
+        0x67332667ffc00b31,
+        0x8eb44a8768581511,
+        0xdb0c2e0d64f98fa7,
+        0x47b5481dbefa4fa4,
+    ];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    pub fn from_state(state: [u64; 8], length: u128) -> Self {
+        Self(Sha512::from_state(state, length))
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha384 = Self::new();
+        sha384.update(message);
+        sha384.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.0.update(message)
+    }
+
+    pub fn finalize(self) -> [u8; Self::OUTPUT_SIZE] {
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        digest.copy_from_slice(&self.0.finalize()[..Self::OUTPUT_SIZE]);
+        digest
+    }
+}
+
+impl Hash for Sha384 {
+    const BLOCK_SIZE: usize = Sha384::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha384::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha384::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha384::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha384::finalize(self).to_vec()
+    }
+}
+
+// SHA-512/256 is SHA-512 with different initial registers and the output cut down to 32 bytes,
+// which hides half of the state
+#[derive(Clone)]
+pub struct Sha512_256(Sha512);
+
+impl Sha512_256 {
+    pub const BLOCK_SIZE: usize = 128;
+    pub const OUTPUT_SIZE: usize = 32;
+    const INITIAL_STATE: [u64; 8] = [
+        0x22312194fc2bf72c,
+        0x9f555fa3c84c64c2,
+        0x2393b86b6f53b151,
+        0x963877195940eabd,
+        0x96283ee2a88effe3,
+        0xbe5e1e2553863992,
+        0x2b0199fc2c85b8aa,
+        0x0eb72ddc81c52ca2,
+    ];
+
+    pub fn new() -> Self {
+        Self::from_state(Self::INITIAL_STATE, 0)
+    }
+
+    pub fn from_state(state: [u64; 8], length: u128) -> Self {
+        Self(Sha512::from_state(state, length))
+    }
+
+    pub fn digest<T>(message: T) -> [u8; Self::OUTPUT_SIZE]
+    where
+        T: AsRef<[u8]>,
+    {
+        let mut sha512_256 = Self::new();
+        sha512_256.update(message);
+        sha512_256.finalize()
+    }
+
+    pub fn update<T>(&mut self, message: T)
+    where
+        T: AsRef<[u8]>,
+    {
+        self.0.update(message)
+    }
+
+    pub fn finalize(self) -> [u8; Self::OUTPUT_SIZE] {
+        let mut digest = [0u8; Self::OUTPUT_SIZE];
+        digest.copy_from_slice(&self.0.finalize()[..Self::OUTPUT_SIZE]);
+        digest
+    }
+}
+
+impl Hash for Sha512_256 {
+    const BLOCK_SIZE: usize = Sha512_256::BLOCK_SIZE;
+    const OUTPUT_SIZE: usize = Sha512_256::OUTPUT_SIZE;
+
+    fn new() -> Self {
+        Sha512_256::new()
+    }
+
+    fn update(&mut self, message: &[u8]) {
+        Sha512_256::update(self, message)
+    }
+
+    fn finalize(self) -> Vec<u8> {
+        Sha512_256::finalize(self).to_vec()
+    }
+}
+
+// the Merkle-Damgard padding SHA-224 and SHA-256 append to a message of `message_length` bytes. it
+// is the same as SHA-1's
+pub fn padding(message_length: u64) -> Vec<u8> {
+    let zeroes = (Sha256::BLOCK_SIZE * 2 - 9 - (message_length as usize % Sha256::BLOCK_SIZE))
+        % Sha256::BLOCK_SIZE;
+
+    [
+        &[0x80][..],
+        &vec![0u8; zeroes],
+        &(message_length * 8).to_be_bytes(),
+    ]
+    .concat()
+}
+
+// the Merkle-Damgard padding the SHA-512 family appends to a message of `message_length` bytes:
+// like SHA-256's, but aligned to 128-byte blocks with the length in bits as a big-endian u128
+pub fn padding_512(message_length: u128) -> Vec<u8> {
+    let zeroes =
+        (Sha512::BLOCK_SIZE * 2 - 17 - (message_length % Sha512::BLOCK_SIZE as u128) as usize)
+            % Sha512::BLOCK_SIZE;
+
+    [
+        &[0x80][..],
+        &vec![0u8; zeroes],
+        &(message_length * 8).to_be_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_sha2() {
+    use crate::helpers::Hex;
+
+    // examples from NIST's FIPS 180 example documents
+    let message1 = b"abc";
+    let message2 =
+        b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopq\
+                     klmnopqrlmnopqrsmnopqrstnopqrstu";
+
+    assert_eq!(
+        Hex::from_bytes(&Sha256::digest(b"")).to_string(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha256::digest(message1)).to_string(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha256::digest(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        ))
+        .to_string(),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha256::digest(&[b'a'; 1_000_000][..])).to_string(),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha224::digest(message1)).to_string(),
+        "23097d223405d8228642a477bda255b32aadbce4bda0b3f7e36c9da7"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha224::digest(&[b'a'; 1_000_000][..])).to_string(),
+        "20794655980c91d8bbb4c1ea97618a4bf03f42581948b2ee4ee7ad67"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha384::digest(message1)).to_string(),
+        "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca1\
+         34c825a7"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha384::digest(&message2[..])).to_string(),
+        "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa\
+         91746039"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha512::digest(b"")).to_string(),
+        "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2\
+         877eec2f63b931bd47417a81a538327af927da3e"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha512::digest(message1)).to_string(),
+        "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23\
+         a3feebbd454d4423643ce80e2a9ac94fa54ca49f"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha512::digest(&message2[..])).to_string(),
+        "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99de\
+         c4b5433ac7d329eeb6dd26545e96e55b874be909"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha512_256::digest(message1)).to_string(),
+        "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"
+    );
+    assert_eq!(
+        Hex::from_bytes(&Sha512_256::digest(&message2[..])).to_string(),
+        "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a"
+    );
+}
+
+#[test]
+fn test_length_extension() {
+    let key = (0..24).map(|_| rand::random::<u8>()).collect::<Vec<u8>>();
+    let message = [&key[..], b"user=bob;expires=2030-01-01"].concat();
+
+    // a full-width digest resumes hashing exactly where the padded message left off
+    let glue = padding(message.len() as u64);
+    let mut sha256 = <Sha256 as LengthExtendable>::from_digest(
+        &Sha256::digest(&message),
+        (message.len() + glue.len()) as u64,
+    )
+    .unwrap();
+    sha256.update(b";admin=true");
+    assert_eq!(
+        sha256.finalize(),
+        Sha256::digest([&message, &glue, &b";admin=true"[..]].concat())
+    );
+
+    // a truncated digest is only part of the state, and there is nothing to resume from
+    let length = Sha512::BLOCK_SIZE as u64;
+    assert!(<Sha512 as LengthExtendable>::from_digest(&Sha512::digest(&message), length).is_some());
+    assert!(<Sha512 as LengthExtendable>::from_digest(&Sha384::digest(&message), length).is_none());
+    assert!(
+        <Sha512 as LengthExtendable>::from_digest(&Sha512_256::digest(&message), length).is_none()
+    );
+    assert!(<Sha256 as LengthExtendable>::from_digest(&Sha224::digest(&message), 0).is_none());
+}
@@ -0,0 +1,225 @@
+use crate::biguint::BigUint;
+use crate::dh::Group;
+use crate::hmac::Hmac;
+use crate::sha2::Sha256;
+
+// SRP-6a (RFC 2945, with the multiplier k = H(N, g) from RFC 5054), over SHA-256. the server
+// never learns the password, only a verifier v = g^x derived from it, and a password guess can
+// only be checked by talking to the server
+
+#[derive(Clone, Debug)]
+pub struct Parameters {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl Parameters {
+    pub fn new(group: &Group) -> Self {
+        let mut parameters = Self {
+            n: group.p.clone(),
+            g: group.g.clone(),
+            k: BigUint::zero(),
+        };
+        parameters.k = hash(&[&group.p.to_bytes_be(), &parameters.pad(&group.g)]);
+
+        parameters
+    }
+
+    // numbers that are hashed together are left-padded to the length of N. anything longer (which
+    // only a misbehaving peer would send) is hashed as it is
+    pub fn pad(&self, x: &BigUint) -> Vec<u8> {
+        x.to_bytes_be_padded(self.n.bits().div_ceil(8))
+            .unwrap_or_else(|| x.to_bytes_be())
+    }
+
+    // u = H(PAD(A) | PAD(B)), which ties the session to both public values
+    pub fn scramble(&self, client_public: &BigUint, server_public: &BigUint) -> BigUint {
+        hash(&[&self.pad(client_public), &self.pad(server_public)])
+    }
+}
+
+pub fn hash(parts: &[&[u8]]) -> BigUint {
+    let mut sha256 = Sha256::new();
+    parts.iter().for_each(|part| sha256.update(part));
+    BigUint::from_bytes_be(&sha256.finalize())
+}
+
+// x = H(salt | H(identity | ":" | password))
+pub fn private_key(salt: &[u8], identity: &str, password: &str) -> BigUint {
+    let inner = Sha256::digest([identity.as_bytes(), b":", password.as_bytes()].concat());
+    hash(&[salt, &inner])
+}
+
+// K = H(S)
+pub fn session_key(secret: &BigUint) -> Vec<u8> {
+    Sha256::digest(secret.to_bytes_be()).to_vec()
+}
+
+// the client proves that it has K without giving it away by MACing the salt with it
+pub fn proof(key: &[u8], salt: &[u8]) -> Vec<u8> {
+    Hmac::<Sha256>::mac(key, salt)
+}
+
+// what the server stores for each user in place of the password
+#[derive(Clone, Debug)]
+pub struct Verifier {
+    pub salt: Vec<u8>,
+    pub v: BigUint,
+}
+
+impl Verifier {
+    pub fn new(parameters: &Parameters, identity: &str, password: &str) -> Self {
+        let salt = (0..16).map(|_| rand::random()).collect::<Vec<u8>>();
+        let x = private_key(&salt, identity, password);
+
+        Self {
+            v: parameters.g.modpow(&x, &parameters.n),
+            salt,
+        }
+    }
+}
+
+pub struct Client {
+    parameters: Parameters,
+    a: BigUint,
+    pub public: BigUint,
+}
+
+impl Client {
+    pub fn new(parameters: &Parameters) -> Self {
+        let a = BigUint::random_below(&parameters.n);
+
+        Self {
+            public: parameters.g.modpow(&a, &parameters.n),
+            parameters: parameters.clone(),
+            a,
+        }
+    }
+
+    // S = (B - kg^x)^(a + ux) mod N
+    pub fn session_key(
+        &self,
+        identity: &str,
+        password: &str,
+        salt: &[u8],
+        server_public: &BigUint,
+    ) -> Result<Vec<u8>, &'static str> {
+        let Parameters { n, g, k } = &self.parameters;
+        if (server_public % n).is_zero() {
+            return Err("the server's public value is 0 mod N");
+        }
+
+        let u = self.parameters.scramble(&self.public, server_public);
+        if u.is_zero() {
+            return Err("the scrambling parameter is 0");
+        }
+
+        let x = private_key(salt, identity, password);
+        let base = &(&(server_public % n) + n) - &(&(k * &g.modpow(&x, n)) % n);
+        let secret = (&base % n).modpow(&(&self.a + &(&u * &x)), n);
+
+        Ok(session_key(&secret))
+    }
+}
+
+pub struct Server {
+    parameters: Parameters,
+    verifier: Verifier,
+    b: BigUint,
+    checks_public: bool,
+    pub public: BigUint,
+}
+
+impl Server {
+    // B = kv + g^b mod N
+    pub fn new(parameters: &Parameters, verifier: &Verifier) -> Self {
+        let Parameters { n, g, k } = parameters;
+        let b = BigUint::random_below(n);
+        let public = &(&(k * &verifier.v) + &g.modpow(&b, n)) % n;
+
+        Self {
+            parameters: parameters.clone(),
+            verifier: verifier.clone(),
+            b,
+            checks_public: true,
+            public,
+        }
+    }
+
+    // a server that skips the check on A that RFC 5054 requires, like plenty of real ones have
+    pub fn naive(parameters: &Parameters, verifier: &Verifier) -> Self {
+        Self {
+            checks_public: false,
+            ..Self::new(parameters, verifier)
+        }
+    }
+
+    pub fn salt(&self) -> &[u8] {
+        &self.verifier.salt
+    }
+
+    // S = (Av^u)^b mod N
+    pub fn session_key(&self, client_public: &BigUint) -> Result<Vec<u8>, &'static str> {
+        let n = &self.parameters.n;
+        if self.checks_public && (client_public % n).is_zero() {
+            return Err("the client's public value is 0 mod N");
+        }
+
+        let u = self.parameters.scramble(client_public, &self.public);
+        let secret = (&(client_public * &self.verifier.v.modpow(&u, n)) % n).modpow(&self.b, n);
+
+        Ok(session_key(&secret))
+    }
+
+    // whether the client's proof shows that it has the same session key, which it can only have
+    // if it knows the password
+    pub fn verify(&self, client_public: &BigUint, client_proof: &[u8]) -> bool {
+        match self.session_key(client_public) {
+            Ok(key) => Hmac::<Sha256>::verify(&key, self.salt(), client_proof),
+            Err(_) => false,
+        }
+    }
+}
+
+#[test]
+fn test_srp() {
+    let parameters = Parameters::new(&Group::modp_2048());
+    let verifier = Verifier::new(&parameters, "alice@example.com", "correct horse");
+
+    let client = Client::new(&parameters);
+    let server = Server::new(&parameters, &verifier);
+
+    let client_key = client
+        .session_key(
+            "alice@example.com",
+            "correct horse",
+            server.salt(),
+            &server.public,
+        )
+        .unwrap();
+    assert_eq!(client_key, server.session_key(&client.public).unwrap());
+    assert!(server.verify(&client.public, &proof(&client_key, server.salt())));
+
+    // the wrong password gives the wrong key
+    let wrong_key = client
+        .session_key(
+            "alice@example.com",
+            "battery staple",
+            server.salt(),
+            &server.public,
+        )
+        .unwrap();
+    assert!(!server.verify(&client.public, &proof(&wrong_key, server.salt())));
+
+    // and degenerate public values are refused
+    assert!(server.session_key(&parameters.n).is_err());
+    assert!(client
+        .session_key(
+            "alice@example.com",
+            "correct horse",
+            server.salt(),
+            &BigUint::zero()
+        )
+        .is_err());
+}
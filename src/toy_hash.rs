@@ -0,0 +1,118 @@
+use crate::block_ciphers::{Aes, EcbCrypter};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// a deliberately weak Merkle-Damgard hash with a state of only a few bits, so that generic attacks
+// on iterated hashes can be run for real. the compression function encrypts the zero-padded state
+// with AES-128, keyed by the message block, and keeps the top `state_bits` bits of the result.
+//
+// every call to the compression function is counted, so that attacks can report what they cost
+pub struct ToyHash {
+    state_bits: u32,
+    initial_state: u32,
+    calls: AtomicU64,
+}
+
+impl ToyHash {
+    pub const BLOCK_SIZE: usize = Aes::BLOCK_SIZE;
+
+    pub fn new(state_bits: u32) -> Self {
+        assert!(
+            (1..=32).contains(&state_bits),
+            "the state must be between 1 and 32 bits"
+        );
+
+        Self {
+            state_bits,
+            initial_state: 0x5eed_c0de >> (32 - state_bits),
+            calls: AtomicU64::new(0),
+        }
+    }
+
+    pub fn state_bits(&self) -> u32 {
+        self.state_bits
+    }
+
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    // the number of times the compression function has been called so far
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub fn compress(&self, state: u32, block: &[u8]) -> u32 {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+
+        let mut key = [0u8; Aes::BLOCK_SIZE];
+        key.copy_from_slice(block);
+
+        let mut plaintext = [0u8; Aes::BLOCK_SIZE];
+        plaintext[..4].copy_from_slice(&(state << (32 - self.state_bits)).to_be_bytes());
+
+        let ciphertext = EcbCrypter::new(key).encrypt(plaintext);
+        u32::from_be_bytes([ciphertext[0], ciphertext[1], ciphertext[2], ciphertext[3]])
+            >> (32 - self.state_bits)
+    }
+
+    // runs the compression function over whole blocks of `message`, starting from `state`. no
+    // padding is added
+    pub fn iterate<T>(&self, state: u32, message: T) -> u32
+    where
+        T: AsRef<[u8]>,
+    {
+        let message = message.as_ref();
+        assert!(
+            message.len().is_multiple_of(Self::BLOCK_SIZE),
+            "the message must be a whole number of blocks"
+        );
+
+        message
+            .chunks(Self::BLOCK_SIZE)
+            .fold(state, |state, block| self.compress(state, block))
+    }
+
+    // hashes the message padded with its length, like any Merkle-Damgard hash
+    pub fn digest<T>(&self, message: T) -> u32
+    where
+        T: AsRef<[u8]>,
+    {
+        let message = message.as_ref();
+        let padded = [message, &padding(message.len() as u64)].concat();
+
+        self.iterate(self.initial_state, padded)
+    }
+}
+
+// the padding appended to a message of `message_length` bytes: a 1 bit, zeroes up to 8 bytes short
+// of a block boundary, then the message length in bits as a big-endian u64
+pub fn padding(message_length: u64) -> Vec<u8> {
+    let zeroes = (ToyHash::BLOCK_SIZE * 2 - 9 - (message_length as usize % ToyHash::BLOCK_SIZE))
+        % ToyHash::BLOCK_SIZE;
+
+    [
+        &[0x80][..],
+        &vec![0u8; zeroes],
+        &(message_length * 8).to_be_bytes(),
+    ]
+    .concat()
+}
+
+#[test]
+fn test_toy_hash() {
+    let hash = ToyHash::new(16);
+    let message = b"YELLOW SUBMARINE is a 16 byte key";
+
+    let digest = hash.digest(message);
+    assert!(digest < 1 << 16);
+    assert_eq!(digest, hash.digest(message));
+    assert_ne!(digest, hash.digest(&message[1..]));
+
+    // the message and its padding fill three blocks
+    assert_eq!(hash.calls(), 9);
+
+    // digesting is iterating over the padded message from the initial state
+    let padded = [&message[..], &padding(message.len() as u64)].concat();
+    assert_eq!(padded.len(), 3 * ToyHash::BLOCK_SIZE);
+    assert_eq!(hash.iterate(hash.initial_state(), &padded), digest);
+}